@@ -0,0 +1,12 @@
+use crate::flush_policy::FlushPolicy;
+use crate::sync_policy::SyncPolicy;
+
+/// Options for [`Format::append_many_with`](crate::format::Format::append_many_with)
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct AppendManyOptions {
+    pub flush: FlushPolicy,
+    pub sync: SyncPolicy,
+    /// Overrides the dialect of the CSV-like formats (`None` means [`Format::csv_options`](crate::format::Format::csv_options))
+    #[cfg(feature = "csv")]
+    pub csv: Option<crate::csv_options::CsvOptions>,
+}
@@ -0,0 +1,150 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use crate::errors::incompatible_header_error::IncompatibleHeaderError;
+use crate::format::Format;
+use std::io::{Read, Write};
+
+/// The prefix of the values in the compact Rust-native formats (see [`Format::binary_header`])
+///
+/// These formats are not self-describing, so a value written with a different layout would otherwise be decoded as garbage.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct BinaryHeader {
+    /// Identifies the format
+    pub magic: [u8; 4],
+    /// Identifies the layout of the encoding (incremented on incompatible changes, e.g. a new major version of the backend or a different backend config)
+    pub version: u16,
+    /// Identifies the layout of the serialized type (supplied by the caller through [`FormatOptions::schema_version`](crate::format_options::FormatOptions::schema_version))
+    pub schema_version: u32,
+}
+
+impl BinaryHeader {
+    pub const LEN: usize = 10;
+
+    /// Bincode 2 with the standard config (see [`bincode_config`](crate::compact_codec::bincode_config))
+    #[cfg(feature = "bincode")]
+    pub const BINCODE: Self = Self {
+        magic: *b"SLBC",
+        version: 1,
+        schema_version: 0,
+    };
+
+    /// Postcard 1 wire format
+    #[cfg(feature = "postcard")]
+    pub const POSTCARD: Self = Self {
+        magic: *b"SLPC",
+        version: 1,
+        schema_version: 0,
+    };
+
+    pub fn with_schema_version(self, schema_version: u32) -> Self {
+        Self {
+            schema_version,
+            ..self
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let [magic0, magic1, magic2, magic3] = self.magic;
+        let [version0, version1] = self.version.to_le_bytes();
+        let [schema0, schema1, schema2, schema3] = self.schema_version.to_le_bytes();
+        [
+            magic0, magic1, magic2, magic3, version0, version1, schema0, schema1, schema2, schema3,
+        ]
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Returns the input after the header
+    pub fn strip<'a>(&self, format: Format, input: &'a [u8]) -> Result<&'a [u8], IncompatibleHeaderError> {
+        match input.split_first_chunk::<{ Self::LEN }>() {
+            Some((header, rest)) => {
+                self.check(format, header)?;
+                Ok(rest)
+            }
+            None => Err(IncompatibleHeaderError::new(format, *self, None)),
+        }
+    }
+
+    pub fn read(&self, format: Format, reader: &mut impl Read) -> Result<(), DeserializeOneError> {
+        let mut header = [0; Self::LEN];
+        match reader.read_exact(&mut header) {
+            Ok(()) => Ok(self.check(format, &header)?),
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => Err(IncompatibleHeaderError::new(format, *self, None).into()),
+            Err(error) => Err(DeserializeOneErrorKind::Io(error).into()),
+        }
+    }
+
+    fn check(&self, format: Format, header: &[u8; Self::LEN]) -> Result<(), IncompatibleHeaderError> {
+        let [
+            magic0,
+            magic1,
+            magic2,
+            magic3,
+            version0,
+            version1,
+            schema0,
+            schema1,
+            schema2,
+            schema3,
+        ] = *header;
+        let found = Self {
+            magic: [magic0, magic1, magic2, magic3],
+            version: u16::from_le_bytes([version0, version1]),
+            schema_version: u32::from_le_bytes([schema0, schema1, schema2, schema3]),
+        };
+        if found.magic != self.magic {
+            return Err(IncompatibleHeaderError::new(format, *self, None));
+        }
+        if found != *self {
+            return Err(IncompatibleHeaderError::new(format, *self, Some(found)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::VariantArray;
+
+    const HEADER: BinaryHeader = BinaryHeader {
+        magic: *b"TEST",
+        version: 2,
+        schema_version: 7,
+    };
+
+    #[test]
+    fn must_strip_and_read_own_header() {
+        let format = Format::VARIANTS[0];
+        let mut input = Vec::new();
+        HEADER.write(&mut input).unwrap();
+        input.extend_from_slice(b"rest");
+        assert_eq!(input[..BinaryHeader::LEN], *b"TEST\x02\x00\x07\x00\x00\x00");
+        assert_eq!(HEADER.strip(format, &input).unwrap(), b"rest");
+        let mut reader = input.as_slice();
+        HEADER.read(format, &mut reader).unwrap();
+        assert_eq!(reader, b"rest");
+    }
+
+    #[test]
+    fn must_reject_other_version_or_magic() {
+        let format = Format::VARIANTS[0];
+        let older = BinaryHeader {
+            version: 1,
+            ..HEADER
+        };
+        assert_eq!(HEADER.strip(format, &older.to_bytes()).unwrap_err(), IncompatibleHeaderError::new(format, HEADER, Some(older)));
+        let other_schema = HEADER.with_schema_version(8);
+        assert_eq!(HEADER.strip(format, &other_schema.to_bytes()).unwrap_err(), IncompatibleHeaderError::new(format, HEADER, Some(other_schema)));
+        assert_eq!(
+            HEADER
+                .strip(format, b"JSON\x02\x00\x07\x00\x00\x00")
+                .unwrap_err(),
+            IncompatibleHeaderError::new(format, HEADER, None)
+        );
+        assert_eq!(HEADER.strip(format, b"TE").unwrap_err(), IncompatibleHeaderError::new(format, HEADER, None));
+        assert!(HEADER.read(format, &mut b"TE".as_slice()).is_err());
+    }
+}
@@ -0,0 +1,467 @@
+use crate::errors::canonicalize_error::CanonicalizeError;
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
+use serde::{Serialize, Serializer};
+use std::cmp::Ordering;
+
+/// An in-memory copy of a serialized value with the map entries sorted by key (recursively)
+///
+/// Serializing it produces the same output regardless of the iteration order of the original maps (e.g. `HashMap`).
+/// The struct fields keep their declaration order, because it's already deterministic.
+#[derive(PartialEq, Clone, Debug)]
+pub enum CanonicalValue {
+    Unit,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<CanonicalValue>),
+    UnitStruct(&'static str),
+    UnitVariant(Variant),
+    NewtypeStruct(&'static str, Box<CanonicalValue>),
+    NewtypeVariant(Variant, Box<CanonicalValue>),
+    Seq(Vec<CanonicalValue>),
+    Tuple(Vec<CanonicalValue>),
+    TupleStruct(&'static str, Vec<CanonicalValue>),
+    TupleVariant(Variant, Vec<CanonicalValue>),
+    Map(Vec<(CanonicalValue, CanonicalValue)>),
+    Struct(&'static str, Vec<(&'static str, CanonicalValue)>),
+    StructVariant(Variant, Vec<(&'static str, CanonicalValue)>),
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct Variant {
+    pub name: &'static str,
+    pub index: u32,
+    pub variant: &'static str,
+}
+
+impl CanonicalValue {
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, CanonicalizeError> {
+        value.serialize(CanonicalValueSerializer)
+    }
+
+    /// Compares the map keys: strings by UTF-16 code units (as in RFC 8785), numbers by value, other keys by their debug representation
+    pub fn cmp_keys(&self, other: &Self) -> Ordering {
+        use CanonicalValue::*;
+        match (self, other) {
+            (Str(left), Str(right)) => left.encode_utf16().cmp(right.encode_utf16()),
+            (Char(left), Char(right)) => left.cmp(right),
+            (Bool(left), Bool(right)) => left.cmp(right),
+            (I64(left), I64(right)) => left.cmp(right),
+            (U64(left), U64(right)) => left.cmp(right),
+            (I64(left), U64(right)) => i128::from(*left).cmp(&i128::from(*right)),
+            (U64(left), I64(right)) => i128::from(*left).cmp(&i128::from(*right)),
+            (F32(left), F32(right)) => left.total_cmp(right),
+            (F64(left), F64(right)) => left.total_cmp(right),
+            (UnitVariant(left), UnitVariant(right)) => left.index.cmp(&right.index),
+            (NewtypeStruct(_, left), NewtypeStruct(_, right)) => left.cmp_keys(right),
+            _ => format!("{self:?}").cmp(&format!("{other:?}")),
+        }
+    }
+}
+
+impl Serialize for CanonicalValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use CanonicalValue::*;
+        match self {
+            Unit => serializer.serialize_unit(),
+            Bool(value) => serializer.serialize_bool(*value),
+            I64(value) => serializer.serialize_i64(*value),
+            U64(value) => serializer.serialize_u64(*value),
+            I128(value) => serializer.serialize_i128(*value),
+            U128(value) => serializer.serialize_u128(*value),
+            F32(value) => serializer.serialize_f32(*value),
+            F64(value) => serializer.serialize_f64(*value),
+            Char(value) => serializer.serialize_char(*value),
+            Str(value) => serializer.serialize_str(value),
+            Bytes(value) => serializer.serialize_bytes(value),
+            None => serializer.serialize_none(),
+            Some(value) => serializer.serialize_some(value),
+            UnitStruct(name) => serializer.serialize_unit_struct(name),
+            UnitVariant(variant) => serializer.serialize_unit_variant(variant.name, variant.index, variant.variant),
+            NewtypeStruct(name, value) => serializer.serialize_newtype_struct(name, value),
+            NewtypeVariant(variant, value) => serializer.serialize_newtype_variant(variant.name, variant.index, variant.variant, value),
+            Seq(items) => serializer.collect_seq(items),
+            Tuple(items) => {
+                let mut tuple = serializer.serialize_tuple(items.len())?;
+                for item in items {
+                    tuple.serialize_element(item)?;
+                }
+                tuple.end()
+            }
+            TupleStruct(name, items) => {
+                let mut tuple = serializer.serialize_tuple_struct(name, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            TupleVariant(variant, items) => {
+                let mut tuple = serializer.serialize_tuple_variant(variant.name, variant.index, variant.variant, items.len())?;
+                for item in items {
+                    tuple.serialize_field(item)?;
+                }
+                tuple.end()
+            }
+            Map(entries) => {
+                let mut map = serializer.serialize_map(Option::Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Struct(name, fields) => {
+                let mut r#struct = serializer.serialize_struct(name, fields.len())?;
+                for (key, value) in fields {
+                    r#struct.serialize_field(key, value)?;
+                }
+                r#struct.end()
+            }
+            StructVariant(variant, fields) => {
+                let mut r#struct = serializer.serialize_struct_variant(variant.name, variant.index, variant.variant, fields.len())?;
+                for (key, value) in fields {
+                    r#struct.serialize_field(key, value)?;
+                }
+                r#struct.end()
+            }
+        }
+    }
+}
+
+pub struct CanonicalValueSerializer;
+
+impl Serializer for CanonicalValueSerializer {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+    type SerializeSeq = SeqBuilder;
+    type SerializeTuple = SeqBuilder;
+    type SerializeTupleStruct = SeqBuilder;
+    type SerializeTupleVariant = SeqBuilder;
+    type SerializeMap = MapBuilder;
+    type SerializeStruct = StructBuilder;
+    type SerializeStructVariant = StructBuilder;
+
+    fn serialize_bool(self, v: bool) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::I64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::I64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::I64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::U64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::U64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::U64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<CanonicalValue, CanonicalizeError> {
+        // `-0.0 == 0.0`, so both zeros become `0.0`
+        Ok(CanonicalValue::F32(if v == 0.0 { 0.0 } else { v }))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::F64(if v == 0.0 { 0.0 } else { v }))
+    }
+
+    fn serialize_char(self, v: char) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Str(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Bytes(v.to_owned()))
+    }
+
+    fn serialize_none(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Some(Box::new(CanonicalValue::from_serialize(value)?)))
+    }
+
+    fn serialize_unit(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::UnitVariant(Variant {
+            name,
+            index: variant_index,
+            variant,
+        }))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(CanonicalValue::NewtypeStruct(name, Box::new(CanonicalValue::from_serialize(value)?)))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<CanonicalValue, CanonicalizeError> {
+        let variant = Variant {
+            name,
+            index: variant_index,
+            variant,
+        };
+        Ok(CanonicalValue::NewtypeVariant(variant, Box::new(CanonicalValue::from_serialize(value)?)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqBuilder, CanonicalizeError> {
+        Ok(SeqBuilder::new(SeqKind::Seq, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqBuilder, CanonicalizeError> {
+        Ok(SeqBuilder::new(SeqKind::Tuple, Some(len)))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SeqBuilder, CanonicalizeError> {
+        Ok(SeqBuilder::new(SeqKind::TupleStruct(name), Some(len)))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<SeqBuilder, CanonicalizeError> {
+        let variant = Variant {
+            name,
+            index: variant_index,
+            variant,
+        };
+        Ok(SeqBuilder::new(SeqKind::TupleVariant(variant), Some(len)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapBuilder, CanonicalizeError> {
+        Ok(MapBuilder {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<StructBuilder, CanonicalizeError> {
+        Ok(StructBuilder {
+            name,
+            variant: None,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<StructBuilder, CanonicalizeError> {
+        let variant = Variant {
+            name,
+            index: variant_index,
+            variant,
+        };
+        Ok(StructBuilder {
+            name,
+            variant: Some(variant),
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum SeqKind {
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(Variant),
+}
+
+pub struct SeqBuilder {
+    kind: SeqKind,
+    items: Vec<CanonicalValue>,
+}
+
+impl SeqBuilder {
+    fn new(kind: SeqKind, len: Option<usize>) -> Self {
+        Self {
+            kind,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.items.push(CanonicalValue::from_serialize(value)?);
+        Ok(())
+    }
+
+    fn build(self) -> CanonicalValue {
+        match self.kind {
+            SeqKind::Seq => CanonicalValue::Seq(self.items),
+            SeqKind::Tuple => CanonicalValue::Tuple(self.items),
+            SeqKind::TupleStruct(name) => CanonicalValue::TupleStruct(name, self.items),
+            SeqKind::TupleVariant(variant) => CanonicalValue::TupleVariant(variant, self.items),
+        }
+    }
+}
+
+impl SerializeSeq for SeqBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
+
+impl SerializeTuple for SeqBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
+
+impl SerializeTupleStruct for SeqBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
+
+impl SerializeTupleVariant for SeqBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
+
+pub struct MapBuilder {
+    entries: Vec<(CanonicalValue, CanonicalValue)>,
+    key: Option<CanonicalValue>,
+}
+
+impl SerializeMap for MapBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), CanonicalizeError> {
+        self.key = Some(CanonicalValue::from_serialize(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| <CanonicalizeError as serde::ser::Error>::custom("serialize_value was called before serialize_key"))?;
+        self.entries
+            .push((key, CanonicalValue::from_serialize(value)?));
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<CanonicalValue, CanonicalizeError> {
+        self.entries
+            .sort_by(|(left, _), (right, _)| left.cmp_keys(right));
+        Ok(CanonicalValue::Map(self.entries))
+    }
+}
+
+pub struct StructBuilder {
+    name: &'static str,
+    variant: Option<Variant>,
+    fields: Vec<(&'static str, CanonicalValue)>,
+}
+
+impl StructBuilder {
+    fn push<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CanonicalizeError> {
+        self.fields
+            .push((key, CanonicalValue::from_serialize(value)?));
+        Ok(())
+    }
+
+    fn build(self) -> CanonicalValue {
+        match self.variant {
+            Some(variant) => CanonicalValue::StructVariant(variant, self.fields),
+            None => CanonicalValue::Struct(self.name, self.fields),
+        }
+    }
+}
+
+impl SerializeStruct for StructBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
+
+impl SerializeStructVariant for StructBuilder {
+    type Ok = CanonicalValue;
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CanonicalizeError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<CanonicalValue, CanonicalizeError> {
+        Ok(self.build())
+    }
+}
@@ -0,0 +1,23 @@
+use crate::cli_command::CliCommand;
+use crate::errors::cli_error::CliError;
+use clap::Parser;
+
+/// Converts, validates and formats the files in the supported formats
+///
+/// The formats are selected by the extensions (or by the flags); `-` means stdin for the inputs and stdout for the outputs.
+#[derive(Parser, Clone, Debug)]
+#[command(name = "save-load", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: CliCommand,
+}
+
+impl Cli {
+    pub fn run(self) -> Result<(), CliError> {
+        match self.command {
+            CliCommand::Convert(command) => command.run(),
+            CliCommand::Validate(command) => command.run(),
+            CliCommand::Fmt(command) => command.run(),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::convert_command::ConvertCommand;
+use crate::fmt_command::FmtCommand;
+use crate::validate_command::ValidateCommand;
+use clap::Subcommand;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum CliCommand {
+    /// Convert a file to another format
+    Convert(ConvertCommand),
+    /// Check that the files can be parsed (the errors are reported with their locations)
+    Validate(ValidateCommand),
+    /// Rewrite the files in the canonical style (sorted keys, RFC 8785 JSON); only the formats without comments are supported (JSON, JSON Lines, XML and CSV)
+    Fmt(FmtCommand),
+}
@@ -0,0 +1,77 @@
+use crate::compression::{Compression, read_decompressed};
+use crate::errors::cli_error::CliError;
+use crate::errors::format_not_detected_error::FormatNotDetectedError;
+use crate::errors::read_file_error::ReadFileError;
+use crate::errors::stdout_format_required_error::StdoutFormatRequiredError;
+use crate::errors::unknown_format_error::UnknownFormatError;
+use crate::format::Format;
+use crate::operation::Operation;
+use crate::write_atomically::write_atomically;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// The path that means stdin for the inputs and stdout for the outputs
+pub const STDIO_PATH: &str = "-";
+
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+/// Reads stdin for `-`, otherwise reads the file (decompressing it if its extension is a compression extension)
+pub fn read_input(path: &Path) -> Result<Vec<u8>, ReadFileError> {
+    let result = if is_stdio(path) {
+        let mut bytes = Vec::new();
+        io::stdin().lock().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        read_decompressed(path)
+    };
+    result.map_err(|source| ReadFileError {
+        path: path.into(),
+        source,
+    })
+}
+
+/// Writes to stdout for `-`, otherwise writes the file atomically (compressing it if its extension is a compression extension)
+pub fn write_output(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if is_stdio(path) {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(bytes)?;
+        return stdout.flush();
+    }
+    write_atomically(path, |file| match Compression::from_path(path) {
+        Some(compression) => compression.write_compressed(file, None, |writer| writer.write_all(bytes)),
+        None => file.write_all(bytes),
+    })
+}
+
+/// Returns the format from the flag, from the extension, or the best guess from the content (for stdin and for the unrecognized extensions)
+pub fn input_format(format: Option<Format>, path: &Path, bytes: &[u8]) -> Result<Format, FormatNotDetectedError> {
+    if let Some(format) = format {
+        return Ok(format);
+    }
+    if !is_stdio(path) {
+        if let Ok(format) = Format::try_from_path(path) {
+            return Ok(format);
+        }
+    }
+    Format::detect(bytes)
+        .first()
+        .map(|guess| guess.format)
+        .ok_or_else(|| FormatNotDetectedError {
+            path: path.into(),
+        })
+}
+
+/// Returns the format from the flag or from the extension (stdout requires the flag)
+pub fn output_format(format: Option<Format>, path: &Path) -> Result<Format, CliError> {
+    match format {
+        Some(format) => Ok(format),
+        None if is_stdio(path) => Err(StdoutFormatRequiredError.into()),
+        None => Format::try_from_path(path).map_err(|source| UnknownFormatError::new(path, Operation::Save, source).into()),
+    }
+}
+
+/// JSON Lines is always converted item by item (its single-document mode reads only the first line)
+pub fn is_many(format: Format, many: bool) -> bool {
+    many || format == Format::Jsonl
+}
@@ -0,0 +1,44 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use serde::de::DeserializeOwned;
+use std::io::Read;
+
+/// The bincode config of [`Format::Bincode`](crate::format::Format::Bincode) (changing it requires a new layout version in [`Format::binary_header`](crate::format::Format::binary_header))
+#[cfg(feature = "bincode")]
+pub fn bincode_config() -> bincode::config::Configuration {
+    bincode::config::standard()
+}
+
+/// Decodes a single value that spans the whole item
+#[cfg(feature = "bincode")]
+pub fn decode_bincode<T: DeserializeOwned>(item: &[u8]) -> Result<T, DeserializeOneError> {
+    let (output, read) = bincode::serde::decode_from_slice(item, bincode_config())?;
+    ensure_consumed(item, read)?;
+    Ok(output)
+}
+
+/// Decodes a single value that spans the whole item
+#[cfg(feature = "postcard")]
+pub fn decode_postcard<T: DeserializeOwned>(item: &[u8]) -> Result<T, DeserializeOneError> {
+    let mut deserializer = postcard::Deserializer::from_bytes(item);
+    let output = serde_path_to_error::deserialize(&mut deserializer).map_err(DeserializeOneError::from_path_to_error)?;
+    let rest = deserializer.finalize()?;
+    ensure_consumed(item, item.len().saturating_sub(rest.len()))?;
+    Ok(output)
+}
+
+pub fn read_to_vec(mut reader: impl Read) -> Result<Vec<u8>, DeserializeOneError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(DeserializeOneErrorKind::Io)?;
+    Ok(bytes)
+}
+
+fn ensure_consumed(item: &[u8], read: usize) -> Result<(), DeserializeOneError> {
+    if read < item.len() {
+        let message = format!("trailing data after the value ({} bytes)", item.len().saturating_sub(read));
+        return Err(DeserializeOneErrorKind::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message)).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use strum::{Display, VariantArray};
+
+/// Compression of a file, selected by its outer extension (e.g. `events.jsonl.gz`)
+#[derive(Serialize, Deserialize, Display, VariantArray, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[non_exhaustive]
+pub enum Compression {
+    #[cfg(feature = "flate2")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "liblzma")]
+    Xz,
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+}
+
+impl Compression {
+    pub fn to_file_extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "flate2")]
+            Compression::Gzip => "gz",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "zst",
+            #[cfg(feature = "liblzma")]
+            Compression::Xz => "xz",
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => "bz2",
+        }
+    }
+
+    pub fn from_file_extension(extension: &OsStr) -> Option<Self> {
+        match extension.to_str() {
+            #[cfg(feature = "flate2")]
+            Some("gz") => Some(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            Some("zst") => Some(Compression::Zstd),
+            #[cfg(feature = "liblzma")]
+            Some("xz") => Some(Compression::Xz),
+            #[cfg(feature = "bzip2")]
+            Some("bz2") => Some(Compression::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// Returns the compression selected by the last extension of the path (`None` if it's not a compression extension)
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(Self::from_file_extension)
+    }
+
+    /// Decompresses the reader (concatenated streams are decompressed as a single stream)
+    #[allow(unused_variables, unreachable_code)]
+    pub fn decoder<'a>(self, reader: impl BufRead + 'a) -> io::Result<Box<dyn Read + 'a>> {
+        Ok(match self {
+            #[cfg(feature = "flate2")]
+            Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+            #[cfg(feature = "liblzma")]
+            Compression::Xz => Box::new(liblzma::bufread::XzDecoder::new_multi_decoder(reader)),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+        })
+    }
+
+    /// Returns the levels accepted by the backend: 0-9 for gzip, 1-22 for zstd, 0-9 for xz, 1-9 for bzip2
+    pub fn levels(self) -> RangeInclusive<u32> {
+        match self {
+            #[cfg(feature = "flate2")]
+            Compression::Gzip => 0..=9,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => 1..=22,
+            #[cfg(feature = "liblzma")]
+            Compression::Xz => 0..=9,
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => 1..=9,
+        }
+    }
+
+    /// Passes a compressing writer to `write`, then finishes the compressed stream
+    ///
+    /// `None` means the default level of the backend. A level outside of [`Compression::levels`] returns [`io::ErrorKind::InvalidInput`] before anything is written (some backends would panic or clamp it silently).
+    #[allow(unused_variables, unreachable_code)]
+    pub fn write_compressed<E: From<io::Error>>(self, writer: impl Write, level: Option<u32>, write: impl FnOnce(&mut dyn Write) -> Result<(), E>) -> Result<(), E> {
+        if let Some(level) = level {
+            let levels = self.levels();
+            if !levels.contains(&level) {
+                let message = format!("compression level {level} is out of range for {self} (expected {}-{})", levels.start(), levels.end());
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, message).into());
+            }
+        }
+        match self {
+            #[cfg(feature = "flate2")]
+            Compression::Gzip => {
+                let level = level.map_or_else(flate2::Compression::default, flate2::Compression::new);
+                let mut encoder = flate2::write::GzEncoder::new(writer, level);
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                // zstd uses its default level for 0
+                let level = level.map_or(0, |level| i32::try_from(level).unwrap_or(0));
+                let mut encoder = zstd::stream::write::Encoder::new(writer, level)?;
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            #[cfg(feature = "liblzma")]
+            Compression::Xz => {
+                let mut encoder = liblzma::write::XzEncoder::new(writer, level.unwrap_or(6));
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => {
+                let level = level.map_or_else(bzip2::Compression::default, bzip2::Compression::new);
+                let mut encoder = bzip2::write::BzEncoder::new(writer, level);
+                write(&mut encoder)?;
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the file, decompressing it while reading if its extension is a compression extension (so only the decompressed content is kept in memory)
+pub fn read_decompressed(path: &Path) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    open_decompressed(path)?.read_to_end(&mut output)?;
+    Ok(output)
+}
+
+/// Opens the file for buffered reading, decompressing it if its extension is a compression extension
+pub fn open_decompressed(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    Ok(match Compression::from_path(path) {
+        Some(compression) => Box::new(BufReader::new(compression.decoder(BufReader::new(file))?)),
+        None => Box::new(BufReader::new(file)),
+    })
+}
+
+/// Decompresses the contents of the file if its extension is a compression extension
+pub fn decompress_for_path(path: &Path, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    match Compression::from_path(path) {
+        Some(compression) => {
+            let mut output = Vec::new();
+            compression
+                .decoder(bytes.as_slice())?
+                .read_to_end(&mut output)?;
+            Ok(output)
+        }
+        None => Ok(bytes),
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+    use crate::save_one_options::SaveOneOptions;
+    use std::fs::read;
+
+    #[test]
+    fn must_round_trip_every_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let value = vec!["a".repeat(100), "b".to_string()];
+        for compression in Compression::VARIANTS {
+            for level in [
+                None,
+                Some(*compression.levels().start()),
+                Some(*compression.levels().end()),
+            ] {
+                let path = dir
+                    .path()
+                    .join(format!("items.json.{}", compression.to_file_extension()));
+                let options = SaveOneOptions {
+                    compression_level: level,
+                    ..SaveOneOptions::default()
+                };
+                Format::Json.save_one_with(&path, &value, &options).unwrap();
+                let bytes = read(&path).unwrap();
+                assert!(!bytes.starts_with(b"["), "{compression} {level:?}");
+                assert_eq!(Format::Json.load_one::<Vec<String>>(&path).unwrap(), value, "{compression} {level:?}");
+                assert_eq!(Format::load_one_as::<Vec<String>>(&path).unwrap(), value, "{compression} {level:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn must_reject_level_out_of_range() {
+        let dir = tempfile::tempdir().unwrap();
+        for compression in Compression::VARIANTS {
+            let path = dir
+                .path()
+                .join(format!("items.json.{}", compression.to_file_extension()));
+            let below = compression.levels().start().checked_sub(1);
+            for level in below
+                .into_iter()
+                .chain([compression.levels().end().saturating_add(1), 50])
+            {
+                let options = SaveOneOptions {
+                    compression_level: Some(level),
+                    ..SaveOneOptions::default()
+                };
+                let error = Format::Json.save_one_with(&path, &1, &options).unwrap_err();
+                assert!(matches!(error.source, crate::errors::write_one_error::WriteOneError::Io(ref error) if error.kind() == io::ErrorKind::InvalidInput), "{compression} {level}: {error}");
+                assert!(!path.exists());
+            }
+        }
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn must_locate_error_in_compressed_file() {
+        use crate::errors::load_one_error_kind::LoadOneErrorKind;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json.gz");
+        Compression::Gzip
+            .write_compressed(File::create(&path).unwrap(), None, |writer| writer.write_all(b"{\n  \"a\": 1,\n  \"b\": x\n}"))
+            .unwrap();
+        let error = Format::Json
+            .load_one::<serde_json::Value>(&path)
+            .unwrap_err();
+        let LoadOneErrorKind::DeserializeOne(source) = &error.source else {
+            panic!("{error}");
+        };
+        assert_eq!(source.location().and_then(|location| location.line), Some(3), "{error}");
+    }
+}
@@ -0,0 +1,42 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use std::io::BufRead;
+
+/// Deserializes the items of a self-delimiting binary format one by one (e.g. a CBOR sequence), until the end of the input
+///
+/// An invalid item yields an error and stops the iteration, because the start of the next item is unknown.
+pub struct ConcatenatedItemsIter<R, F> {
+    reader: R,
+    deserialize: F,
+    is_finished: bool,
+}
+
+impl<R: BufRead, T, F: FnMut(&mut R) -> Result<T, DeserializeOneError>> ConcatenatedItemsIter<R, F> {
+    pub fn new(reader: R, deserialize: F) -> Self {
+        Self {
+            reader,
+            deserialize,
+            is_finished: false,
+        }
+    }
+}
+
+impl<R: BufRead, T, F: FnMut(&mut R) -> Result<T, DeserializeOneError>> Iterator for ConcatenatedItemsIter<R, F> {
+    type Item = Result<T, DeserializeOneError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+        let result = match self.reader.fill_buf() {
+            Ok([]) => {
+                self.is_finished = true;
+                return None;
+            }
+            Ok(_) => (self.deserialize)(&mut self.reader),
+            Err(error) => Err(DeserializeOneErrorKind::Io(error).into()),
+        };
+        self.is_finished = result.is_err();
+        Some(result)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::cli_io::{input_format, is_many, output_format, read_input, write_output};
+use crate::errors::cli_error::CliError;
+use crate::errors::load_many_error::LoadManyError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::save_one_error::SaveOneError;
+use crate::errors::transcode_error::TranscodeError;
+use crate::errors::transcode_many_error::TranscodeManyError;
+use crate::format::Format;
+use clap::Args;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct ConvertCommand {
+    /// The input file (`-` for stdin)
+    pub input: PathBuf,
+    /// The output file (`-` for stdout)
+    pub output: PathBuf,
+    /// The input format (detected from the extension or from the content by default)
+    #[arg(long)]
+    pub from: Option<Format>,
+    /// The output format (selected by the extension by default, required for stdout)
+    #[arg(long)]
+    pub to: Option<Format>,
+    /// Convert the items one by one (e.g. a JSON array to JSON Lines, or YAML documents to CSV rows), implied for JSON Lines
+    #[arg(long)]
+    pub many: bool,
+}
+
+impl ConvertCommand {
+    /// The output is written only if the conversion succeeds
+    pub fn run(self) -> Result<(), CliError> {
+        let input = read_input(&self.input)?;
+        let from = input_format(self.from, &self.input, &input)?;
+        let to = output_format(self.to, &self.output)?;
+        let mut output = Vec::new();
+        if is_many(from, self.many) || is_many(to, self.many) {
+            Format::transcode_many(from, to, Cursor::new(input.clone()), &mut output).map_err(|error| match error {
+                TranscodeManyError::Deserialize(error) => LoadOneError::from_input(&self.input, from, error, &input).into(),
+                TranscodeManyError::DeserializeMany(error) => LoadManyError::new(&self.input, from, error).into(),
+                error => CliError::from(error),
+            })?;
+        } else {
+            Format::transcode(from, to, input.as_slice(), &mut output).map_err(|error| match error {
+                TranscodeError::Deserialize(error) => LoadOneError::from_input(&self.input, from, error, &input).into(),
+                TranscodeError::Serialize(error) => SaveOneError::new(&self.output, to, error).into(),
+                error => CliError::from(error),
+            })?;
+        }
+        write_output(&self.output, &output).map_err(|source| SaveOneError::new(&self.output, to, source))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use std::cell::Cell;
+use std::io::{self, BufRead, Read};
+use std::rc::Rc;
+
+/// Counts the bytes that the reader has read or consumed (to locate the errors of the backends that don't report a position)
+///
+/// The count is shared, so it can be read after the reader is moved into a deserializer. It is the end of the last input that the backend has read, so it is at (or shortly after) the error.
+#[derive(Debug)]
+pub struct CountingReader<R> {
+    pub inner: R,
+    pub count: Rc<Cell<usize>>,
+}
+
+impl<R> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            count: Rc::default(),
+        }
+    }
+
+    fn add(&self, len: usize) {
+        self.count.set(self.count.get().saturating_add(len));
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.add(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_count_read_and_consumed_bytes() {
+        let mut reader = CountingReader::new(&b"abcdef"[..]);
+        let count = reader.count.clone();
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(count.get(), 2);
+        assert_eq!(reader.fill_buf().unwrap(), b"cdef");
+        assert_eq!(count.get(), 2);
+        reader.consume(3);
+        assert_eq!(count.get(), 5);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::csv_quote_style::CsvQuoteStyle;
+use crate::csv_terminator::CsvTerminator;
+use serde::{Deserialize, Serialize};
+
+/// Dialect of a delimiter-separated file (see [`Format::csv_options`](crate::format::Format::csv_options) for the defaults of each format)
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub quote_style: CsvQuoteStyle,
+    /// Whether the first record contains the column names (if `false`, the fields are matched by position)
+    pub has_headers: bool,
+    pub terminator: CsvTerminator,
+}
+
+impl CsvOptions {
+    pub fn with_delimiter(delimiter: u8) -> Self {
+        Self {
+            delimiter,
+            ..Self::default()
+        }
+    }
+
+    pub fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .quoting(self.quote_style != CsvQuoteStyle::Never)
+            .has_headers(self.has_headers)
+            .terminator(self.terminator.to_reader_terminator());
+        builder
+    }
+
+    /// The incremental parser with the dialect of [`Self::reader_builder`], for the readers that feed it the input themselves (e.g. the async records stream)
+    pub fn core_reader_builder(&self) -> csv_core::ReaderBuilder {
+        let mut builder = csv_core::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .quoting(self.quote_style != CsvQuoteStyle::Never)
+            .terminator(self.terminator.to_core_reader_terminator());
+        builder
+    }
+
+    pub fn writer_builder(&self) -> csv::WriterBuilder {
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .quote_style(self.quote_style.into())
+            .has_headers(self.has_headers)
+            .terminator(self.terminator.to_writer_terminator());
+        builder
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: CsvQuoteStyle::default(),
+            has_headers: true,
+            terminator: CsvTerminator::default(),
+        }
+    }
+}
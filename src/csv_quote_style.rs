@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, VariantArray};
+
+/// When the CSV writer quotes the fields
+#[derive(Serialize, Deserialize, Display, VariantArray, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum CsvQuoteStyle {
+    /// Quote only the fields that contain the delimiter, the quote or the terminator
+    #[default]
+    Necessary,
+    Always,
+    /// Quote all fields that are not numbers
+    NonNumeric,
+    /// Never quote (the output can't be read back if a field contains the delimiter, the quote or the terminator)
+    Never,
+}
+
+impl From<CsvQuoteStyle> for csv::QuoteStyle {
+    fn from(style: CsvQuoteStyle) -> Self {
+        match style {
+            CsvQuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            CsvQuoteStyle::Always => csv::QuoteStyle::Always,
+            CsvQuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+            CsvQuoteStyle::Never => csv::QuoteStyle::Never,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::csv_options::CsvOptions;
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use csv_core::ReadRecordResult;
+use futures_util::Stream;
+use futures_util::stream::unfold;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// Deserializes the records one by one, feeding the buffered input to a single incremental parser (so the quotes, the escapes and the terminators are read like in [`Format::deserialize_many_from_reader`](crate::format::Format::deserialize_many_from_reader))
+///
+/// A record that fails to deserialize is reported and skipped, like in the sync API; the stream ends after an I/O error.
+pub fn csv_records_stream<T: DeserializeOwned>(reader: impl AsyncBufRead + Unpin, options: CsvOptions) -> impl Stream<Item = Result<T, DeserializeOneError>> {
+    let state = CsvRecordsState {
+        reader,
+        parser: options.core_reader_builder().build(),
+        has_headers: options.has_headers,
+        headers: None,
+        fields: vec![0; 1024],
+        ends: vec![0; 16],
+        position: csv::Position::new(),
+        is_done: false,
+    };
+    unfold(state, |mut state| async move {
+        if state.is_done {
+            return None;
+        }
+        let result = state.next_item().await.transpose()?;
+        state.is_done = matches!(&result, Err(error) if matches!(*error.source, DeserializeOneErrorKind::Io(_)));
+        Some((result, state))
+    })
+}
+
+struct CsvRecordsState<R> {
+    reader: R,
+    parser: csv_core::Reader,
+    has_headers: bool,
+    headers: Option<csv::ByteRecord>,
+    /// The buffers of the parser, reused for every record
+    fields: Vec<u8>,
+    ends: Vec<usize>,
+    /// The position of the next record
+    position: csv::Position,
+    is_done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> CsvRecordsState<R> {
+    async fn next_item<T: DeserializeOwned>(&mut self) -> Result<Option<T>, DeserializeOneError> {
+        loop {
+            let Some(record) = self.next_record().await? else {
+                return Ok(None);
+            };
+            if self.has_headers && self.headers.is_none() {
+                self.headers = Some(record);
+                continue;
+            }
+            return Ok(Some(record.deserialize(self.headers.as_ref())?));
+        }
+    }
+
+    async fn next_record(&mut self) -> Result<Option<csv::ByteRecord>, DeserializeOneError> {
+        let (mut fields_len, mut ends_len) = (0, 0);
+        let mut bytes_count = 0usize;
+        loop {
+            // An empty input means the end of the file to the parser
+            let input = self
+                .reader
+                .fill_buf()
+                .await
+                .map_err(|error| DeserializeOneError::new(DeserializeOneErrorKind::Io(error)))?;
+            let (result, input_len, output_len, output_ends_len) = self
+                .parser
+                .read_record(input, &mut self.fields[fields_len..], &mut self.ends[ends_len..]);
+            self.reader.consume(input_len);
+            bytes_count = bytes_count.saturating_add(input_len);
+            fields_len = fields_len.saturating_add(output_len);
+            ends_len = ends_len.saturating_add(output_ends_len);
+            match result {
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => self.fields.resize(self.fields.len().saturating_mul(2), 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len().saturating_mul(2), 0),
+                ReadRecordResult::Record => break,
+                ReadRecordResult::End => return Ok(None),
+            }
+        }
+        let mut record = csv::ByteRecord::new();
+        let mut start = 0;
+        for end in &self.ends[..ends_len] {
+            record.push_field(&self.fields[start..*end]);
+            start = *end;
+        }
+        let position = self.position.clone();
+        record.set_position(Some(position.clone()));
+        self.position
+            .set_byte(
+                position
+                    .byte()
+                    .saturating_add(u64::try_from(bytes_count).unwrap_or(u64::MAX)),
+            )
+            .set_line(self.parser.line())
+            .set_record(position.record().saturating_add(1));
+        Ok(Some(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_terminator::CsvTerminator;
+    use futures_util::StreamExt;
+
+    #[derive(serde::Deserialize, PartialEq, Debug)]
+    struct Row {
+        id: u32,
+        name: String,
+    }
+
+    fn read(input: &'static str, options: CsvOptions) -> Vec<Result<Row, DeserializeOneError>> {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(csv_records_stream(input.as_bytes(), options).collect())
+    }
+
+    fn row(id: u32, name: &str) -> Row {
+        Row {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn must_report_bad_record_and_continue() {
+        let items = read("id,name\n1,a\nx,b\n3,c\n", CsvOptions::default());
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap(), &row(1, "a"));
+        let error = items[1].as_ref().unwrap_err();
+        assert_eq!(error.location().and_then(|location| location.line), Some(3), "{error}");
+        assert_eq!(items[2].as_ref().unwrap(), &row(3, "c"));
+    }
+
+    #[test]
+    fn must_read_records_like_sync_reader() {
+        let input = "id,name\r\n1,\"a\r\nb\"\r2,\"c,\"\"d\"\"\"\n\n3,e";
+        let items = read(input, CsvOptions::default())
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let expected = crate::format::Format::Csv
+            .deserialize_many_from_reader::<Row>(input.as_bytes())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![row(1, "a\r\nb"), row(2, "c,\"d\""), row(3, "e")]);
+        assert_eq!(items, expected);
+        let options = CsvOptions {
+            terminator: CsvTerminator::Byte(b';'),
+            ..CsvOptions::default()
+        };
+        let items = read("id,name;1,a;2,b", options);
+        assert_eq!(items.into_iter().map(Result::unwrap).collect::<Vec<_>>(), vec![row(1, "a"), row(2, "b")]);
+    }
+}
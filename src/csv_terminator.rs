@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// The record terminator
+#[derive(Serialize, Deserialize, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum CsvTerminator {
+    /// Write `\n`, read `\n`, `\r\n` or `\r`
+    #[default]
+    Lf,
+    /// Write `\r\n`, read `\n`, `\r\n` or `\r`
+    Crlf,
+    /// Write and read the byte
+    Byte(u8),
+}
+
+impl CsvTerminator {
+    pub fn to_reader_terminator(self) -> csv::Terminator {
+        match self {
+            CsvTerminator::Lf | CsvTerminator::Crlf => csv::Terminator::CRLF,
+            CsvTerminator::Byte(byte) => csv::Terminator::Any(byte),
+        }
+    }
+
+    pub fn to_core_reader_terminator(self) -> csv_core::Terminator {
+        match self {
+            CsvTerminator::Lf | CsvTerminator::Crlf => csv_core::Terminator::CRLF,
+            CsvTerminator::Byte(byte) => csv_core::Terminator::Any(byte),
+        }
+    }
+
+    pub fn to_writer_terminator(self) -> csv::Terminator {
+        match self {
+            CsvTerminator::Lf => csv::Terminator::Any(b'\n'),
+            CsvTerminator::Crlf => csv::Terminator::CRLF,
+            CsvTerminator::Byte(byte) => csv::Terminator::Any(byte),
+        }
+    }
+
+    /// The bytes written after each record
+    pub fn to_bytes(self) -> Vec<u8> {
+        match self {
+            CsvTerminator::Lf => b"\n".to_vec(),
+            CsvTerminator::Crlf => b"\r\n".to_vec(),
+            CsvTerminator::Byte(byte) => vec![byte],
+        }
+    }
+}
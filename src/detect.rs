@@ -0,0 +1,341 @@
+use crate::format::Format;
+use crate::format_guess::FormatGuess;
+
+/// The number of non-empty lines inspected by the line-based heuristics
+const SAMPLE_LINES: usize = 20;
+
+/// Guesses the format from the content (see [`Format::detect`])
+#[allow(unused_mut, unused_variables)]
+pub fn detect(input: &[u8]) -> Vec<FormatGuess> {
+    let input = input.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(input);
+    let text = String::from_utf8_lossy(input);
+    let text = text.trim_start();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .take(SAMPLE_LINES)
+        .collect::<Vec<_>>();
+    let mut guesses = Vec::<FormatGuess>::new();
+    let mut guess = |format: Format, score: u8| match guesses.iter_mut().find(|guess| guess.format == format) {
+        Some(guess) => guess.score = guess.score.max(score),
+        None => guesses.push(FormatGuess {
+            format,
+            score,
+        }),
+    };
+    let starts_like_json = text.starts_with(['{', '[']);
+    #[cfg(feature = "serde_json")]
+    if starts_like_json {
+        let is_valid = serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok();
+        guess(Format::Json, if is_valid { 100 } else { 60 });
+    }
+    #[cfg(feature = "serde-jsonlines")]
+    if starts_like_json {
+        let is_object_line = |line: &&str| (line.starts_with('{') && line.ends_with('}')) || (line.starts_with('[') && line.ends_with(']'));
+        let is_every_line_an_object = lines.iter().all(is_object_line);
+        match lines.len() {
+            0 | 1 => guess(Format::Jsonl, 50),
+            _ if is_every_line_an_object => guess(Format::Jsonl, 90),
+            _ => {}
+        }
+    }
+    // Strict JSON is also valid in the lenient dialects, so they get lower scores
+    let starts_like_lenient_json = starts_like_json || text.starts_with("//") || text.starts_with("/*");
+    // The same parser reads both dialects, so JSONC (the more common one) wins the tie
+    #[cfg(feature = "json5")]
+    if starts_like_lenient_json {
+        let is_valid = json5::from_str::<serde::de::IgnoredAny>(text).is_ok();
+        let has_comments = text.contains("//") || text.contains("/*");
+        match (is_valid, has_comments) {
+            (true, _) => {
+                guess(Format::Jsonc, 90);
+                guess(Format::Json5, 85);
+            }
+            (false, true) => guess(Format::Jsonc, 50),
+            (false, false) => {}
+        }
+    }
+    #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
+    if text.starts_with("<?xml") {
+        guess(Format::Xml, 100);
+    } else if text.starts_with('<') && text[1..].starts_with(|char: char| char.is_alphabetic() || char == '!' || char == '_') {
+        guess(Format::Xml, 80);
+    }
+    #[cfg(feature = "serde_yaml")]
+    if text.starts_with("---") || text.starts_with("%YAML") {
+        guess(Format::Yaml, 90);
+    } else if !starts_like_json && is_majority(&lines, is_yaml_line) {
+        guess(Format::Yaml, 50);
+    }
+    #[cfg(feature = "toml")]
+    {
+        let has_table_headers = lines.iter().any(|line| is_toml_table_header(line));
+        let has_key_values = is_majority(&lines, is_toml_key_value);
+        if has_table_headers || has_key_values {
+            let is_valid = toml::from_str::<toml::Table>(text).is_ok_and(|table| !table.is_empty());
+            let score = match (is_valid, has_table_headers) {
+                (true, _) => 95,
+                (false, true) => 80,
+                (false, false) => 70,
+            };
+            guess(Format::Toml, score);
+        }
+    }
+    #[cfg(feature = "ron")]
+    if text.starts_with("#![enable(") {
+        guess(Format::Ron, 100);
+    } else if starts_like_ron_struct(text) {
+        let is_valid = ron::from_str::<serde::de::IgnoredAny>(text).is_ok();
+        guess(Format::Ron, if is_valid { 95 } else { 60 });
+    }
+    // A valid TOML document is also valid INI, so INI gets a lower score (but a higher one than invalid TOML, e.g. with unquoted strings)
+    #[cfg(feature = "rust-ini")]
+    if lines.iter().any(|line| is_ini_section_header(line)) && crate::ini_tree::parse(text).is_ok() {
+        guess(Format::Ini, 85);
+    }
+    // Only the `key=value` lines count (the `key: value` lines look like YAML)
+    #[cfg(feature = "properties")]
+    {
+        let mut entries = lines
+            .iter()
+            .filter(|line| !line.starts_with('!'))
+            .peekable();
+        if entries.peek().is_some() && entries.all(|line| is_properties_entry(line)) && crate::properties::parse(text).is_ok() {
+            guess(Format::Properties, 75);
+        }
+    }
+    #[cfg(feature = "csv")]
+    if !starts_like_json && !text.starts_with('<') {
+        for (delimiter, format) in [
+            (',', Format::Csv),
+            ('\t', Format::Tsv),
+            ('|', Format::Psv),
+            (';', Format::SemicolonCsv),
+        ] {
+            let mut counts = lines.iter().map(|line| line.matches(delimiter).count());
+            let Some(first) = counts.next().filter(|count| *count > 0) else {
+                continue;
+            };
+            if counts.all(|count| count == first) {
+                guess(format, if lines.len() > 1 { 60 } else { 30 });
+            }
+        }
+    }
+    guesses.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then(left.format.cmp(&right.format))
+    });
+    guesses
+}
+
+#[cfg(any(feature = "serde_yaml", feature = "toml"))]
+fn is_majority(lines: &[&str], predicate: impl Fn(&str) -> bool) -> bool {
+    let matching = lines.iter().filter(|line| predicate(line)).count();
+    matching > 0 && matching >= lines.len().saturating_sub(matching)
+}
+
+#[cfg(feature = "serde_yaml")]
+fn is_yaml_line(line: &str) -> bool {
+    let is_key_value = line
+        .split_once(':')
+        .is_some_and(|(key, value)| !key.is_empty() && !key.contains([' ', '=', '{', '[', '<']) && (value.is_empty() || value.starts_with(' ')));
+    is_key_value || line.starts_with("- ") || line == "-"
+}
+
+/// Returns `true` for an unnamed struct or tuple (e.g. `(port: 80)`) or a named one (e.g. `Config(port: 80)`)
+#[cfg(feature = "ron")]
+fn starts_like_ron_struct(text: &str) -> bool {
+    let name_length = text
+        .find(|char: char| !(char.is_alphanumeric() || char == '_'))
+        .unwrap_or(text.len());
+    let (name, rest) = text.split_at(name_length);
+    !name.starts_with(|char: char| char.is_ascii_digit()) && rest.starts_with('(')
+}
+
+#[cfg(feature = "rust-ini")]
+fn is_ini_section_header(line: &str) -> bool {
+    let name = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'));
+    name.is_some_and(|name| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|char| char.is_alphanumeric() || matches!(char, '_' | '-' | '.' | ' '))
+    })
+}
+
+#[cfg(feature = "properties")]
+fn is_properties_entry(line: &str) -> bool {
+    line.split_once('=').is_some_and(|(key, _)| {
+        let key = key.trim();
+        !key.is_empty() && !key.contains(|char: char| char.is_whitespace() || matches!(char, '[' | '{' | '<' | '"'))
+    })
+}
+
+#[cfg(feature = "toml")]
+fn is_toml_table_header(line: &str) -> bool {
+    let name = line
+        .strip_prefix("[[")
+        .and_then(|line| line.strip_suffix("]]"))
+        .or_else(|| {
+            line.strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+        });
+    name.is_some_and(|name| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|char| char.is_alphanumeric() || matches!(char, '_' | '-' | '.' | '"' | ' '))
+    })
+}
+
+#[cfg(feature = "toml")]
+fn is_toml_key_value(line: &str) -> bool {
+    line.split_once('=').is_some_and(|(key, _)| {
+        let key = key.trim();
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|char| char.is_alphanumeric() || matches!(char, '_' | '-' | '.' | '"' | ' '))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::VariantArray;
+
+    /// Returns the guesses among the formats, best guess first (the scores don't depend on the other enabled formats, so each test needs only the formats it ranks)
+    fn guesses(input: &str, formats: &[Format]) -> Vec<(Format, u8)> {
+        detect(input.as_bytes())
+            .into_iter()
+            .filter(|guess| formats.contains(&guess.format))
+            .map(|guess| (guess.format, guess.score))
+            .collect()
+    }
+
+    /// The binary formats are not detected, so only the text formats have samples (a sample is the best guess among any of the enabled formats)
+    #[test]
+    #[allow(unused_variables)]
+    fn must_detect_every_text_format() {
+        let assert_detected = |input: &str, format: Format| {
+            let guesses = guesses(input, Format::VARIANTS);
+            assert_eq!(guesses.first().map(|guess| guess.0), Some(format), "{input:?}: {guesses:?}");
+        };
+        #[cfg(feature = "serde_json")]
+        {
+            assert_detected("{\"name\": \"a\", \"port\": 80}", Format::Json);
+            assert_detected("[1, 2]", Format::Json);
+        }
+        #[cfg(feature = "serde-jsonlines")]
+        assert_detected("{\"id\": 1}\n{\"id\": 2}\n", Format::Jsonl);
+        #[cfg(feature = "json5")]
+        {
+            assert_detected("// settings\n{\"name\": \"a\"}", Format::Jsonc);
+            assert_detected("{name: 'a', port: 80,}", Format::Jsonc);
+        }
+        #[cfg(feature = "serde_yaml")]
+        {
+            assert_detected("name: a\nport: 80\n", Format::Yaml);
+            assert_detected("---\nname: a\n", Format::Yaml);
+            assert_detected("- a\n- b\n", Format::Yaml);
+        }
+        #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
+        {
+            assert_detected("<?xml version=\"1.0\"?>\n<config><port>80</port></config>", Format::Xml);
+            assert_detected("<config><port>80</port></config>", Format::Xml);
+        }
+        #[cfg(feature = "toml")]
+        {
+            assert_detected("name = \"a\"\nport = 80\n", Format::Toml);
+            assert_detected("[server]\nport = 80\n", Format::Toml);
+        }
+        #[cfg(feature = "rust-ini")]
+        assert_detected("[server]\nname = a b\n", Format::Ini);
+        #[cfg(feature = "properties")]
+        assert_detected("name=a\nport=80\n", Format::Properties);
+        #[cfg(feature = "csv")]
+        {
+            assert_detected("name,port\na,80\nb,443\n", Format::Csv);
+            assert_detected("name\tport\na\t80\n", Format::Tsv);
+            assert_detected("name|port\na|80\n", Format::Psv);
+            assert_detected("name;port\na;80\n", Format::SemicolonCsv);
+        }
+        #[cfg(feature = "ron")]
+        {
+            assert_detected("(name: \"a\", port: 80)", Format::Ron);
+            assert_detected("Config(name: \"a\")", Format::Ron);
+            assert_detected("#![enable(implicit_some)]\n(port: 80)", Format::Ron);
+        }
+    }
+
+    #[cfg(all(feature = "toml", feature = "rust-ini", feature = "properties"))]
+    #[test]
+    fn must_rank_key_value_formats() {
+        let formats = [Format::Toml, Format::Ini, Format::Properties];
+        // Valid TOML is also valid INI and (without sections) valid properties, so TOML wins while its values are typed
+        assert_eq!(guesses("[server]\nport = 80\n", &formats), vec![(Format::Toml, 95), (Format::Ini, 85)]);
+        assert_eq!(guesses("port = 80\n", &formats), vec![(Format::Toml, 95), (Format::Properties, 75)]);
+        assert_eq!(guesses("port=80\n", &formats), vec![(Format::Toml, 95), (Format::Properties, 75)]);
+        // The unquoted strings are invalid TOML
+        assert_eq!(guesses("[server]\nname = a b\n", &formats), vec![(Format::Ini, 85), (Format::Toml, 80)]);
+        assert_eq!(guesses("server.port = 80\nserver.name = a\n", &formats), vec![(Format::Properties, 75), (Format::Toml, 70)]);
+        assert_eq!(guesses("name=a\n", &formats), vec![(Format::Properties, 75), (Format::Toml, 70)]);
+    }
+
+    #[cfg(all(feature = "serde_json", feature = "serde-jsonlines", feature = "json5"))]
+    #[test]
+    fn must_rank_json_dialects() {
+        let formats = [Format::Json, Format::Jsonl, Format::Jsonc, Format::Json5];
+        // Strict JSON is also valid in the lenient dialects, which win only when the input is not strict JSON
+        assert_eq!(
+            guesses("{\"port\": 80}", &formats),
+            vec![
+                (Format::Json, 100),
+                (Format::Jsonc, 90),
+                (Format::Json5, 85),
+                (Format::Jsonl, 50)
+            ]
+        );
+        assert_eq!(
+            guesses("{\"port\": 80,}", &formats),
+            vec![
+                (Format::Jsonc, 90),
+                (Format::Json5, 85),
+                (Format::Json, 60),
+                (Format::Jsonl, 50)
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "serde_yaml", feature = "properties"))]
+    #[test]
+    fn must_tell_yaml_from_properties() {
+        let formats = [Format::Yaml, Format::Properties];
+        // The `key: value` lines are YAML, the `key=value` lines are properties
+        assert_eq!(guesses("port: 80\n", &formats), vec![(Format::Yaml, 50)]);
+        assert_eq!(guesses("port=80\n", &formats), vec![(Format::Properties, 75)]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_break_ties_by_format_order() {
+        let formats = [Format::Csv, Format::Tsv, Format::Psv, Format::SemicolonCsv];
+        assert_eq!(guesses("a,b;c\n1,2;3\n", &formats), vec![(Format::Csv, 60), (Format::SemicolonCsv, 60)]);
+        assert_eq!(guesses("a|b\tc\n1|2\t3\n", &formats), vec![(Format::Tsv, 60), (Format::Psv, 60)]);
+    }
+
+    #[test]
+    fn must_detect_nothing_in_blank_input() {
+        assert_eq!(guesses("", Format::VARIANTS), vec![]);
+        assert_eq!(guesses(" \n\t\n", Format::VARIANTS), vec![]);
+        assert_eq!(guesses("\u{feff}", Format::VARIANTS), vec![]);
+    }
+}
@@ -1,15 +1,64 @@
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod append_many_error;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod append_many_error_kind;
+pub mod binary_format_error;
+pub mod canonicalize_error;
+#[cfg(feature = "cli")]
+pub mod cli_error;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod compressed_append_error;
+#[cfg(feature = "cli")]
+pub mod data_not_preserved_error;
 pub mod deserialize_many_error;
 pub mod deserialize_one_error;
+pub mod deserialize_one_error_kind;
+pub mod format_not_detected_error;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+pub mod incompatible_header_error;
+#[cfg(feature = "properties")]
+pub mod invalid_escape_error;
+#[cfg(feature = "cli")]
+pub mod invalid_files_error;
 pub mod item_not_found_error;
 pub mod load_many_as_error;
 pub mod load_many_error;
+pub mod load_many_error_kind;
 pub mod load_one_as_error;
+pub mod load_one_auto_error;
 pub mod load_one_error;
+pub mod load_one_error_kind;
+#[cfg(feature = "lock")]
+pub mod load_one_locked_error;
+#[cfg(feature = "lock")]
+pub mod lock_error;
+#[cfg(feature = "lock")]
+pub mod lock_error_kind;
+#[cfg(feature = "lock")]
+pub mod lock_timeout_error;
+#[cfg(feature = "serde-transcode")]
+pub mod lossy_value_error;
 pub mod path_has_no_extension_error;
+pub mod read_file_error;
 pub mod save_one_as_error;
 pub mod save_one_error;
 pub mod serialize_many_error;
 pub mod serialize_one_error;
+#[cfg(feature = "cli")]
+pub mod stdout_format_required_error;
+#[cfg(feature = "string-tree")]
+pub mod string_tree_error;
+#[cfg(feature = "toml")]
+pub mod toml_array_not_found_error;
+#[cfg(feature = "serde-transcode")]
+pub mod transcode_error;
+#[cfg(feature = "serde-transcode")]
+pub mod transcode_many_error;
 pub mod try_from_path_error;
+pub mod unknown_format_error;
 pub mod unrecognized_extension_error;
 pub mod unsupported_format_error;
+#[cfg(feature = "lock")]
+pub mod update_one_error;
+pub mod write_one_error;
+pub(crate) mod write_path_error;
@@ -0,0 +1,33 @@
+use crate::errors::append_many_error_kind::AppendManyErrorKind;
+use crate::errors::write_path_error::write_path_error;
+use crate::format::Format;
+use crate::operation::Operation;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Error, Debug)]
+pub struct AppendManyError {
+    pub path: PathBuf,
+    pub format: Format,
+    pub operation: Operation,
+    pub source: AppendManyErrorKind,
+}
+
+impl AppendManyError {
+    pub fn new(path: impl Into<PathBuf>, format: Format, source: impl Into<AppendManyErrorKind>) -> Self {
+        let source = source.into();
+        Self {
+            path: path.into(),
+            format,
+            operation: source.operation(),
+            source,
+        }
+    }
+}
+
+impl Display for AppendManyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_path_error(f, self.operation, &self.path, self.format, &self.source)
+    }
+}
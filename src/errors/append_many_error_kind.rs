@@ -0,0 +1,22 @@
+use crate::errors::compressed_append_error::CompressedAppendError;
+use crate::errors::serialize_one_error::SerializeOneError;
+use crate::errors::unsupported_format_error::UnsupportedFormatError;
+use crate::operation::Operation;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum AppendManyErrorKind {
+    Io(std::io::Error),
+    SerializeOne(SerializeOneError),
+    UnsupportedFormat(UnsupportedFormatError),
+    Compressed(CompressedAppendError),
+}
+
+impl AppendManyErrorKind {
+    pub fn operation(&self) -> Operation {
+        match self {
+            AppendManyErrorKind::Io(_) | AppendManyErrorKind::UnsupportedFormat(_) | AppendManyErrorKind::Compressed(_) => Operation::Save,
+            AppendManyErrorKind::SerializeOne(_) => Operation::Serialize,
+        }
+    }
+}
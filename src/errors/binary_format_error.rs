@@ -0,0 +1,14 @@
+use crate::format::Format;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct BinaryFormatError {
+    pub format: Format,
+}
+
+impl Display for BinaryFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is a binary format, so it can't be written as text (use the byte-oriented methods, e.g. `serialize_one_to_vec`)", self.format)
+    }
+}
@@ -0,0 +1,21 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct CanonicalizeError {
+    pub message: String,
+}
+
+impl Display for CanonicalizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not canonicalize the value: {}", self.message)
+    }
+}
+
+impl serde::ser::Error for CanonicalizeError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
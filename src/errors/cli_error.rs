@@ -0,0 +1,41 @@
+use crate::errors::data_not_preserved_error::DataNotPreservedError;
+use crate::errors::format_not_detected_error::FormatNotDetectedError;
+use crate::errors::invalid_files_error::InvalidFilesError;
+use crate::errors::load_many_error::LoadManyError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::read_file_error::ReadFileError;
+use crate::errors::save_one_error::SaveOneError;
+use crate::errors::stdout_format_required_error::StdoutFormatRequiredError;
+use crate::errors::transcode_error::TranscodeError;
+use crate::errors::transcode_many_error::TranscodeManyError;
+use crate::errors::unknown_format_error::UnknownFormatError;
+use crate::errors::unsupported_format_error::UnsupportedFormatError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+#[non_exhaustive]
+pub enum CliError {
+    Read(ReadFileError),
+    UnknownFormat(UnknownFormatError),
+    FormatNotDetected(FormatNotDetectedError),
+    StdoutFormatRequired(StdoutFormatRequiredError),
+    /// The input could not be deserialized (the error keeps the input to show the location)
+    Load(LoadOneError),
+    LoadMany(LoadManyError),
+    Transcode(TranscodeError),
+    TranscodeMany(TranscodeManyError),
+    Save(SaveOneError),
+    InvalidFiles(InvalidFilesError),
+    UnsupportedFormat(UnsupportedFormatError),
+    DataNotPreserved(DataNotPreservedError),
+}
+
+impl CliError {
+    /// Renders the deserialization errors with the offending source lines (see [`LoadOneError::render`]), the other errors as their messages
+    pub fn render(&self) -> String {
+        match self {
+            CliError::Load(error) => error.render(),
+            error => error.to_string(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::compression::Compression;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+/// The items would be appended uncompressed to a compressed file, corrupting it
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct CompressedAppendError {
+    pub compression: Compression,
+}
+
+impl Display for CompressedAppendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "appending to a {} compressed file is not supported", self.compression)
+    }
+}
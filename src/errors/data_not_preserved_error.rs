@@ -0,0 +1,16 @@
+use crate::format::Format;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct DataNotPreservedError {
+    pub path: PathBuf,
+    pub format: Format,
+}
+
+impl Display for DataNotPreservedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not format {} as {}: the output would lose data (e.g. comments or the precision of numbers), so the file is left unchanged", self.path.display(), self.format)
+    }
+}
@@ -8,4 +8,6 @@ pub enum DeserializeManyError {
     UnsupportedFormat(UnsupportedFormatError),
     DeserializeOneError(DeserializeOneError),
     IoError(std::io::Error),
+    #[cfg(feature = "toml")]
+    TomlArrayNotFound(crate::errors::toml_array_not_found_error::TomlArrayNotFoundError),
 }
@@ -1,22 +1,251 @@
-use derive_more::{Display, Error, From};
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use crate::location::Location;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
 
-#[derive(Error, Display, From, Debug)]
-#[non_exhaustive]
-pub enum DeserializeOneError {
+#[derive(Error, Debug)]
+pub struct DeserializeOneError {
+    /// Boxed to keep the `Result`s small (some backend errors are large)
+    pub source: Box<DeserializeOneErrorKind>,
+    /// The path to the field that failed to deserialize (e.g. `servers[2].port`), `None` if the error is at the root or the path is not tracked for this format
+    pub field_path: Option<String>,
+    pub location: Option<Location>,
+}
+
+impl DeserializeOneError {
+    pub fn new(source: impl Into<DeserializeOneErrorKind>) -> Self {
+        let source = source.into();
+        Self {
+            location: source.location(),
+            field_path: None,
+            source: Box::new(source),
+        }
+    }
+
+    pub fn from_path_to_error<E: Into<DeserializeOneErrorKind>>(error: serde_path_to_error::Error<E>) -> Self {
+        Self::from_path_to_error_with(error, Into::into)
+    }
+
+    /// Converts the backend error with `into_kind` (e.g. to attach the position that only the deserializer knows)
+    pub fn from_path_to_error_with<E>(error: serde_path_to_error::Error<E>, into_kind: impl FnOnce(E) -> DeserializeOneErrorKind) -> Self {
+        let is_known = error
+            .path()
+            .iter()
+            .any(|segment| !matches!(segment, serde_path_to_error::Segment::Unknown));
+        let field_path = is_known.then(|| error.path().to_string());
+        Self {
+            field_path,
+            ..Self::new(into_kind(error.into_inner()))
+        }
+    }
+
+    /// Sets the byte offset if the backend error has no location (for backends that report no position, the offset is tracked while reading the input)
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        if self.location.is_none() {
+            self.location = Some(Location {
+                line: None,
+                column: None,
+                offset: Some(offset),
+            });
+        }
+        self
+    }
+
+    /// Resolves the line and the column from the byte offset (for backends that report only the offset)
+    pub fn with_input(mut self, input: &[u8]) -> Self {
+        if let Some(Location {
+            line: None,
+            offset: Some(offset),
+            ..
+        }) = self.location
+        {
+            self.location = Some(Location::from_offset(input, offset));
+        }
+        self
+    }
+
+    /// Converts the column from bytes to characters (for backends that count the column in bytes)
+    pub fn with_byte_column(mut self, input: &[u8]) -> Self {
+        if let Some(Location {
+            line: Some(line),
+            column: Some(column),
+            offset: None,
+        }) = self.location
+        {
+            self.location = Some(Location::from_byte_column(input, line, column));
+        }
+        self
+    }
+
+    pub fn location(&self) -> Option<Location> {
+        self.location
+    }
+
+    /// Returns the field path and the message without the location (e.g. `servers[2].port: invalid digit found in string`)
+    pub fn message(&self) -> String {
+        let message = self.source.message();
+        match &self.field_path {
+            Some(field_path) if !self.source.includes_field_path() && !message.starts_with(&format!("{field_path}: ")) => format!("{field_path}: {message}"),
+            _ => message,
+        }
+    }
+}
+
+/// Writes `location: field_path: message` (e.g. `14:7: servers[2].port: invalid digit found in string`)
+impl Display for DeserializeOneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{location}: {}", self.message()),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl From<DeserializeOneErrorKind> for DeserializeOneError {
+    fn from(source: DeserializeOneErrorKind) -> Self {
+        Self::new(source)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Error> for DeserializeOneError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "json5")]
+impl From<json5::Error> for DeserializeOneError {
+    fn from(error: json5::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl From<serde_yaml::Error> for DeserializeOneError {
+    fn from(error: serde_yaml::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "serde-xml-rs")]
+impl From<serde_xml_rs::Error> for DeserializeOneError {
+    fn from(error: serde_xml_rs::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "quick-xml")]
+impl From<quick_xml::DeError> for DeserializeOneError {
+    fn from(error: quick_xml::DeError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for DeserializeOneError {
+    fn from(error: toml::de::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for DeserializeOneError {
+    fn from(error: csv::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "ron")]
+impl From<ron::error::SpannedError> for DeserializeOneError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "rust-ini")]
+impl From<ini::ParseError> for DeserializeOneError {
+    fn from(error: ini::ParseError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "properties")]
+impl From<crate::errors::invalid_escape_error::InvalidEscapeError> for DeserializeOneError {
+    fn from(error: crate::errors::invalid_escape_error::InvalidEscapeError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "string-tree")]
+impl From<crate::errors::string_tree_error::StringTreeError> for DeserializeOneError {
+    fn from(error: crate::errors::string_tree_error::StringTreeError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "ciborium")]
+impl From<ciborium::de::Error<std::io::Error>> for DeserializeOneError {
+    fn from(error: ciborium::de::Error<std::io::Error>) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "rmp-serde")]
+impl From<rmp_serde::decode::Error> for DeserializeOneError {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::error::DecodeError> for DeserializeOneError {
+    fn from(error: bincode::error::DecodeError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl From<postcard::Error> for DeserializeOneError {
+    fn from(error: postcard::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+impl From<crate::errors::incompatible_header_error::IncompatibleHeaderError> for DeserializeOneError {
+    fn from(error: crate::errors::incompatible_header_error::IncompatibleHeaderError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(any(feature = "csv", feature = "serde-jsonlines"))]
+impl From<crate::errors::item_not_found_error::ItemNotFoundError> for DeserializeOneError {
+    fn from(error: crate::errors::item_not_found_error::ItemNotFoundError) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(any(feature = "json5", feature = "toml", feature = "rust-ini", feature = "properties"))]
+impl From<std::str::Utf8Error> for DeserializeOneError {
+    fn from(error: std::str::Utf8Error) -> Self {
+        Self::new(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
     #[cfg(feature = "serde_json")]
-    SerdeJson(serde_json::Error),
-    #[cfg(feature = "serde-jsonlines")]
-    SerdeJsonlines(std::io::Error),
-    #[cfg(feature = "serde_yaml")]
-    SerdeYaml(serde_yaml::Error),
-    #[cfg(feature = "serde-xml-rs")]
-    SerdeXmlRs(serde_xml_rs::Error),
-    #[cfg(feature = "quick-xml")]
-    QuickXml(quick_xml::DeError),
-    #[cfg(feature = "toml")]
-    Toml(toml::de::Error),
-    #[cfg(feature = "csv")]
-    Csv(csv::Error),
-    #[cfg(any(feature = "csv", feature = "serde-jsonlines"))]
-    ItemNotFound(crate::errors::item_not_found_error::ItemNotFoundError),
+    #[test]
+    fn must_display_location_then_field_path_then_message() {
+        #[derive(serde::Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Server {
+            port: u16,
+        }
+        let error = crate::format::Format::Json
+            .deserialize_one::<Vec<Server>>("[\n  {\"port\": 1},\n  {\"port\": \"x\"}\n]")
+            .unwrap_err();
+        assert_eq!(error.to_string(), "3:14: [1].port: invalid type: string \"x\", expected u16");
+    }
 }
@@ -0,0 +1,174 @@
+use crate::location::Location;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+#[non_exhaustive]
+pub enum DeserializeOneErrorKind {
+    #[from(ignore)]
+    Io(std::io::Error),
+    #[cfg(feature = "serde_json")]
+    SerdeJson(serde_json::Error),
+    #[cfg(feature = "serde-jsonlines")]
+    SerdeJsonlines(std::io::Error),
+    #[cfg(feature = "json5")]
+    Json5(json5::Error),
+    #[cfg(feature = "serde_yaml")]
+    SerdeYaml(serde_yaml::Error),
+    #[cfg(feature = "serde-xml-rs")]
+    SerdeXmlRs(serde_xml_rs::Error),
+    #[cfg(feature = "quick-xml")]
+    QuickXml(quick_xml::DeError),
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+    #[cfg(feature = "ron")]
+    Ron(ron::error::SpannedError),
+    #[cfg(feature = "rust-ini")]
+    Ini(ini::ParseError),
+    #[cfg(feature = "properties")]
+    Properties(crate::errors::invalid_escape_error::InvalidEscapeError),
+    #[cfg(feature = "string-tree")]
+    StringTree(crate::errors::string_tree_error::StringTreeError),
+    #[cfg(feature = "ciborium")]
+    Ciborium(ciborium::de::Error<std::io::Error>),
+    #[cfg(feature = "rmp-serde")]
+    RmpSerde(rmp_serde::decode::Error),
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::error::DecodeError),
+    #[cfg(feature = "postcard")]
+    Postcard(postcard::Error),
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    IncompatibleHeader(crate::errors::incompatible_header_error::IncompatibleHeaderError),
+    #[cfg(any(feature = "csv", feature = "serde-jsonlines"))]
+    ItemNotFound(crate::errors::item_not_found_error::ItemNotFoundError),
+    /// The input of a text format whose parser reads only strings is not valid UTF-8
+    #[cfg(any(feature = "json5", feature = "toml", feature = "rust-ini", feature = "properties"))]
+    Utf8(std::str::Utf8Error),
+}
+
+impl DeserializeOneErrorKind {
+    #[allow(unreachable_patterns)]
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            #[cfg(feature = "serde_json")]
+            DeserializeOneErrorKind::SerdeJson(error) if error.line() != 0 => Some(Location {
+                line: Some(error.line()),
+                column: Some(error.column()),
+                offset: None,
+            }),
+            #[cfg(feature = "json5")]
+            DeserializeOneErrorKind::Json5(json5::Error::Message {
+                location: Some(location),
+                ..
+            }) => Some(Location {
+                line: Some(location.line),
+                column: Some(location.column),
+                offset: None,
+            }),
+            #[cfg(feature = "serde_yaml")]
+            DeserializeOneErrorKind::SerdeYaml(error) => error.location().map(|location| Location {
+                line: Some(location.line()),
+                column: Some(location.column()),
+                offset: Some(location.index()),
+            }),
+            #[cfg(feature = "toml")]
+            DeserializeOneErrorKind::Toml(error) => error.span().map(|span| Location {
+                line: None,
+                column: None,
+                offset: Some(span.start),
+            }),
+            #[cfg(feature = "csv")]
+            DeserializeOneErrorKind::Csv(error) => error.position().map(|position| Location {
+                line: usize::try_from(position.line()).ok(),
+                column: None,
+                offset: usize::try_from(position.byte()).ok(),
+            }),
+            #[cfg(feature = "ron")]
+            DeserializeOneErrorKind::Ron(error) => Some(Location {
+                line: Some(error.span.start.line),
+                column: Some(error.span.start.col),
+                offset: None,
+            }),
+            #[cfg(feature = "rust-ini")]
+            DeserializeOneErrorKind::Ini(error) => Some(Location {
+                line: Some(error.line),
+                column: Some(error.col),
+                offset: None,
+            }),
+            #[cfg(feature = "properties")]
+            DeserializeOneErrorKind::Properties(error) => Some(Location {
+                line: Some(error.line),
+                column: None,
+                offset: None,
+            }),
+            #[cfg(feature = "string-tree")]
+            DeserializeOneErrorKind::StringTree(error) => error.line.map(|line| Location {
+                line: Some(line),
+                column: None,
+                offset: None,
+            }),
+            #[cfg(feature = "ciborium")]
+            DeserializeOneErrorKind::Ciborium(ciborium::de::Error::Syntax(offset) | ciborium::de::Error::Semantic(Some(offset), _)) => Some(Location {
+                line: None,
+                column: None,
+                offset: Some(*offset),
+            }),
+            #[cfg(any(feature = "json5", feature = "toml", feature = "rust-ini", feature = "properties"))]
+            DeserializeOneErrorKind::Utf8(error) => Some(Location {
+                line: None,
+                column: None,
+                offset: Some(error.valid_up_to()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the message of the backend error without the location that some backends put into it (the location is reported by [`Self::location`])
+    #[allow(unreachable_patterns)]
+    pub fn message(&self) -> String {
+        match self {
+            #[cfg(feature = "serde_json")]
+            DeserializeOneErrorKind::SerdeJson(error) => without_location(error.to_string(), self.location()),
+            #[cfg(feature = "serde_yaml")]
+            DeserializeOneErrorKind::SerdeYaml(error) => without_location(error.to_string(), self.location()),
+            #[cfg(feature = "toml")]
+            DeserializeOneErrorKind::Toml(error) => error.message().trim_end().to_string(),
+            #[cfg(feature = "csv")]
+            DeserializeOneErrorKind::Csv(error) => match error.kind() {
+                csv::ErrorKind::Deserialize {
+                    err,
+                    ..
+                } => err.to_string(),
+                _ => error.to_string(),
+            },
+            #[cfg(feature = "ron")]
+            DeserializeOneErrorKind::Ron(error) => error.code.to_string(),
+            #[cfg(feature = "rust-ini")]
+            DeserializeOneErrorKind::Ini(error) => error.msg.to_string(),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns `true` if the backend error message already contains the field path
+    pub fn includes_field_path(&self) -> bool {
+        match self {
+            #[cfg(feature = "serde_yaml")]
+            DeserializeOneErrorKind::SerdeYaml(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Removes the ` at line L column C` that serde_json and serde_yaml put into their messages
+#[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+fn without_location(message: String, location: Option<Location>) -> String {
+    match location {
+        Some(Location {
+            line: Some(line),
+            column: Some(column),
+            ..
+        }) => message.replacen(&format!(" at line {line} column {column}"), "", 1),
+        _ => message,
+    }
+}
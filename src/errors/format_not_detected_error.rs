@@ -0,0 +1,14 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct FormatNotDetectedError {
+    pub path: PathBuf,
+}
+
+impl Display for FormatNotDetectedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not detect the format of {} from its extension or its content", self.path.display())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::binary_header::BinaryHeader;
+use crate::format::Format;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct IncompatibleHeaderError {
+    pub format: Format,
+    pub expected: BinaryHeader,
+    /// The header of the input, `None` if the input doesn't start with the magic of the format
+    pub found: Option<BinaryHeader>,
+}
+
+impl IncompatibleHeaderError {
+    pub fn new(format: Format, expected: BinaryHeader, found: Option<BinaryHeader>) -> Self {
+        Self {
+            format,
+            expected,
+            found,
+        }
+    }
+}
+
+impl Display for IncompatibleHeaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(found) if found.version != self.expected.version => write!(f, "the input was written with {} layout version {}, but this build reads only version {}", self.format, found.version, self.expected.version),
+            Some(found) => write!(f, "the input was written with schema version {}, but schema version {} was expected", found.schema_version, self.expected.schema_version),
+            None => write!(f, "the input doesn't start with the {} header (it was written by another format or without save-load)", self.format),
+        }
+    }
+}
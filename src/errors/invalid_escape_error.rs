@@ -0,0 +1,14 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct InvalidEscapeError {
+    /// One-based
+    pub line: usize,
+}
+
+impl Display for InvalidEscapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "malformed \\uXXXX escape")
+    }
+}
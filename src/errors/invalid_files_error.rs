@@ -0,0 +1,14 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct InvalidFilesError {
+    pub invalid_count: usize,
+    pub total_count: usize,
+}
+
+impl Display for InvalidFilesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {} files are invalid", self.invalid_count, self.total_count)
+    }
+}
@@ -1,10 +1,10 @@
 use derive_more::{Display, Error, From};
 
 use crate::errors::load_many_error::LoadManyError;
-use crate::errors::try_from_path_error::TryFromPathError;
+use crate::errors::unknown_format_error::UnknownFormatError;
 
 #[derive(Error, Display, From, Debug)]
 pub enum LoadManyAsError {
-    UnknownFormat(TryFromPathError),
+    UnknownFormat(UnknownFormatError),
     LoadMany(LoadManyError),
 }
@@ -1,8 +1,55 @@
 use crate::errors::deserialize_many_error::DeserializeManyError;
-use derive_more::{Display, Error, From};
+use crate::errors::load_many_error_kind::LoadManyErrorKind;
+use crate::errors::write_path_error::{write_deserialize_error, write_path_error};
+use crate::format::Format;
+use crate::operation::Operation;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
-#[derive(Error, Display, From, Debug)]
-pub enum LoadManyError {
-    Io(std::io::Error),
-    DeserializeMany(DeserializeManyError),
+#[derive(Error, Debug)]
+pub struct LoadManyError {
+    pub path: PathBuf,
+    pub format: Format,
+    pub operation: Operation,
+    pub source: LoadManyErrorKind,
+}
+
+impl LoadManyError {
+    pub fn new(path: impl Into<PathBuf>, format: Format, source: impl Into<LoadManyErrorKind>) -> Self {
+        let source = source.into();
+        Self {
+            path: path.into(),
+            format,
+            operation: source.operation(),
+            source,
+        }
+    }
+}
+
+impl Display for LoadManyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            LoadManyErrorKind::DeserializeMany(DeserializeManyError::DeserializeOneError(error)) => write_deserialize_error(f, self.operation, &self.path, self.format, error),
+            source => write_path_error(f, self.operation, &self.path, self.format, source),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde_json")]
+    use super::*;
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_display_path_format_and_operation() {
+        let error = LoadManyError::new("items.json", Format::Json, std::io::Error::new(std::io::ErrorKind::NotFound, "not found"));
+        assert_eq!(error.to_string(), "Could not load items.json as Json: not found");
+        let source = Format::Json
+            .deserialize_one::<Vec<u16>>("[\n  1,\n  \"x\"\n]")
+            .unwrap_err();
+        let error = LoadManyError::new("items.json", Format::Json, DeserializeManyError::from(source));
+        assert_eq!(error.to_string(), "Could not deserialize items.json:3:5 as Json: [1]: invalid type: string \"x\", expected u16");
+    }
 }
@@ -0,0 +1,18 @@
+use crate::errors::deserialize_many_error::DeserializeManyError;
+use crate::operation::Operation;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum LoadManyErrorKind {
+    Io(std::io::Error),
+    DeserializeMany(DeserializeManyError),
+}
+
+impl LoadManyErrorKind {
+    pub fn operation(&self) -> Operation {
+        match self {
+            LoadManyErrorKind::Io(_) => Operation::Load,
+            LoadManyErrorKind::DeserializeMany(_) => Operation::Deserialize,
+        }
+    }
+}
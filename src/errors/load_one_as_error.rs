@@ -1,10 +1,49 @@
 use derive_more::{Display, Error, From};
 
 use crate::errors::load_one_error::LoadOneError;
-use crate::errors::try_from_path_error::TryFromPathError;
+use crate::errors::unknown_format_error::UnknownFormatError;
 
 #[derive(Error, Display, From, Debug)]
 pub enum LoadOneAsError {
-    UnknownFormat(TryFromPathError),
+    UnknownFormat(UnknownFormatError),
     LoadOne(LoadOneError),
 }
+
+impl LoadOneAsError {
+    /// Renders the error with the offending source lines, a caret under the error location, and a help hint
+    #[cfg(feature = "miette")]
+    pub fn render(&self) -> String {
+        crate::render_diagnostic::render_diagnostic(self)
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for LoadOneAsError {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        match self {
+            LoadOneAsError::UnknownFormat(_) => Some(Box::new("save_load::unknown_format")),
+            LoadOneAsError::LoadOne(error) => error.code(),
+        }
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        match self {
+            LoadOneAsError::UnknownFormat(_) => Some(Box::new("use a file extension of a supported format or call `Format::load_one` directly")),
+            LoadOneAsError::LoadOne(error) => error.help(),
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        match self {
+            LoadOneAsError::UnknownFormat(_) => None,
+            LoadOneAsError::LoadOne(error) => error.source_code(),
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        match self {
+            LoadOneAsError::UnknownFormat(_) => None,
+            LoadOneAsError::LoadOne(error) => error.labels(),
+        }
+    }
+}
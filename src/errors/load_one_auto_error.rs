@@ -0,0 +1,12 @@
+use derive_more::{Display, Error, From};
+
+use crate::errors::format_not_detected_error::FormatNotDetectedError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::read_file_error::ReadFileError;
+
+#[derive(Error, Display, From, Debug)]
+pub enum LoadOneAutoError {
+    Read(ReadFileError),
+    FormatNotDetected(FormatNotDetectedError),
+    LoadOne(LoadOneError),
+}
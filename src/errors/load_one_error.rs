@@ -1,9 +1,125 @@
-use derive_more::{Display, Error, From};
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
-use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::load_one_error_kind::LoadOneErrorKind;
+use crate::errors::write_path_error::{write_deserialize_error, write_path_error};
+use crate::format::Format;
+use crate::operation::Operation;
+#[cfg(feature = "miette")]
+use crate::source_text::SourceText;
 
-#[derive(Error, Display, From, Debug)]
-pub enum LoadOneError {
-    Io(std::io::Error),
-    DeserializeOne(DeserializeOneError),
+#[derive(Error, Debug)]
+pub struct LoadOneError {
+    pub path: PathBuf,
+    pub format: Format,
+    pub operation: Operation,
+    pub source: LoadOneErrorKind,
+    /// The input that failed to deserialize (boxed to keep the `Result`s small), `None` if the error happened before the input was read
+    #[cfg(feature = "miette")]
+    #[error(not(source))]
+    pub input: Option<Box<SourceText>>,
+}
+
+impl LoadOneError {
+    pub fn new(path: impl Into<PathBuf>, format: Format, source: impl Into<LoadOneErrorKind>) -> Self {
+        let source = source.into();
+        Self {
+            path: path.into(),
+            format,
+            operation: source.operation(),
+            source,
+            #[cfg(feature = "miette")]
+            input: None,
+        }
+    }
+
+    /// Keeps the input for rendering (except for the binary formats, which can't be shown as source lines)
+    #[cfg(feature = "miette")]
+    pub fn from_input(path: impl Into<PathBuf>, format: Format, source: impl Into<LoadOneErrorKind>, input: &[u8]) -> Self {
+        let path = path.into();
+        let input = (!format.is_binary()).then(|| Box::new(SourceText::new(path.display().to_string(), input)));
+        Self {
+            input,
+            ..Self::new(path, format, source)
+        }
+    }
+
+    /// Doesn't copy the input, because only the rendering needs it
+    #[cfg(not(feature = "miette"))]
+    pub fn from_input(path: impl Into<PathBuf>, format: Format, source: impl Into<LoadOneErrorKind>, _input: &[u8]) -> Self {
+        Self::new(path, format, source)
+    }
+
+    /// Renders the error with the offending source lines, a caret under the error location, and a help hint
+    #[cfg(feature = "miette")]
+    pub fn render(&self) -> String {
+        crate::render_diagnostic::render_diagnostic(self)
+    }
+}
+
+impl Display for LoadOneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            LoadOneErrorKind::DeserializeOne(error) => write_deserialize_error(f, self.operation, &self.path, self.format, error),
+            LoadOneErrorKind::Io(error) => write_path_error(f, self.operation, &self.path, self.format, error),
+        }
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for LoadOneError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(format!("save_load::{}", self.operation)))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        match &self.source {
+            LoadOneErrorKind::Io(_) => Some(Box::new(format!("check that {} exists and is readable", self.path.display()))),
+            LoadOneErrorKind::DeserializeOne(error) => match &error.field_path {
+                Some(field_path) => Some(Box::new(format!("check the value of `{field_path}`"))),
+                None => Some(Box::new(format!("check that the file is valid {}", self.format))),
+            },
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        self.input
+            .as_deref()
+            .map(|input| input as &dyn miette::SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let LoadOneErrorKind::DeserializeOne(error) = &self.source else {
+            return None;
+        };
+        let input = self.input.as_deref()?;
+        let offset = error.location()?.to_offset(&input.text)?;
+        let len = input
+            .text
+            .get(offset..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(0, char::len_utf8);
+        let label = miette::LabeledSpan::new_primary_with_span(None, (offset, len));
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde_json")]
+    use super::*;
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_display_path_format_and_operation() {
+        let error = LoadOneError::new("config.json", Format::Json, std::io::Error::new(std::io::ErrorKind::NotFound, "not found"));
+        assert_eq!(error.to_string(), "Could not load config.json as Json: not found");
+        let input = b"{\n  \"port\": \"x\"\n}";
+        let source = Format::Json
+            .deserialize_one_from_slice::<std::collections::BTreeMap<String, u16>>(input)
+            .unwrap_err();
+        let error = LoadOneError::from_input("config.json", Format::Json, source, input);
+        assert_eq!(error.to_string(), "Could not deserialize config.json:2:13 as Json: port: invalid type: string \"x\", expected u16");
+    }
 }
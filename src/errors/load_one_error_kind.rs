@@ -0,0 +1,19 @@
+use derive_more::{Display, Error, From};
+
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::operation::Operation;
+
+#[derive(Error, Display, From, Debug)]
+pub enum LoadOneErrorKind {
+    Io(std::io::Error),
+    DeserializeOne(DeserializeOneError),
+}
+
+impl LoadOneErrorKind {
+    pub fn operation(&self) -> Operation {
+        match self {
+            LoadOneErrorKind::Io(_) => Operation::Load,
+            LoadOneErrorKind::DeserializeOne(_) => Operation::Deserialize,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::lock_error::LockError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum LoadOneLockedError {
+    Lock(LockError),
+    Load(LoadOneError),
+}
@@ -0,0 +1,26 @@
+use crate::errors::lock_error_kind::LockErrorKind;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Error, Debug)]
+pub struct LockError {
+    /// The path of the sidecar (see [`FileLock`](crate::file_lock::FileLock))
+    pub path: PathBuf,
+    pub source: LockErrorKind,
+}
+
+impl LockError {
+    pub fn new(path: impl Into<PathBuf>, source: impl Into<LockErrorKind>) -> Self {
+        Self {
+            path: path.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl Display for LockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not lock {}: {}", self.path.display(), self.source)
+    }
+}
@@ -0,0 +1,8 @@
+use crate::errors::lock_timeout_error::LockTimeoutError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum LockErrorKind {
+    Io(std::io::Error),
+    Timeout(LockTimeoutError),
+}
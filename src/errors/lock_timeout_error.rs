@@ -0,0 +1,14 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct LockTimeoutError {
+    pub timeout: Duration,
+}
+
+impl Display for LockTimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the lock is still held by another owner after {:?}", self.timeout)
+    }
+}
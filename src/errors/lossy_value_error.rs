@@ -0,0 +1,28 @@
+use crate::format::Format;
+use crate::lossy_value_kind::LossyValueKind;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct LossyValueError {
+    pub kind: LossyValueKind,
+    /// The path to the value (e.g. `[2].servers[0].port`), empty for the root value
+    pub path: String,
+    pub from: Format,
+    pub to: Format,
+}
+
+impl Display for LossyValueError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            kind,
+            path,
+            from,
+            to,
+        } = self;
+        match path.as_str() {
+            "" => write!(f, "Could not transcode {from} to {to}: the root value is a {kind}, which {to} can't represent"),
+            path => write!(f, "Could not transcode {from} to {to}: `{path}` is a {kind}, which {to} can't represent"),
+        }
+    }
+}
@@ -9,6 +9,6 @@ pub struct PathHasNoExtensionError {
 
 impl Display for PathHasNoExtensionError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Path has no extension: {:?}", self.path)
+        write!(f, "Path has no extension: {}", self.path.display())
     }
 }
@@ -0,0 +1,15 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Error, Debug)]
+pub struct ReadFileError {
+    pub path: PathBuf,
+    pub source: std::io::Error,
+}
+
+impl Display for ReadFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not read {}: {}", self.path.display(), self.source)
+    }
+}
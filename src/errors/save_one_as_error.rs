@@ -1,10 +1,10 @@
 use derive_more::{Display, Error, From};
 
 use crate::errors::save_one_error::SaveOneError;
-use crate::errors::try_from_path_error::TryFromPathError;
+use crate::errors::unknown_format_error::UnknownFormatError;
 
 #[derive(Error, Display, From, Debug)]
 pub enum SaveOneAsError {
-    UnknownFormat(TryFromPathError),
+    UnknownFormat(UnknownFormatError),
     SaveOne(SaveOneError),
 }
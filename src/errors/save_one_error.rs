@@ -1,8 +1,51 @@
-use crate::errors::serialize_one_error::SerializeOneError;
-use derive_more::{Display, Error, From};
+use crate::errors::write_one_error::WriteOneError;
+use crate::errors::write_path_error::write_path_error;
+use crate::format::Format;
+use crate::operation::Operation;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
-#[derive(Error, Display, From, Debug)]
-pub enum SaveOneError {
-    Io(std::io::Error),
-    SerializeOne(SerializeOneError),
+#[derive(Error, Debug)]
+pub struct SaveOneError {
+    pub path: PathBuf,
+    pub format: Format,
+    pub operation: Operation,
+    pub source: WriteOneError,
+}
+
+impl SaveOneError {
+    pub fn new(path: impl Into<PathBuf>, format: Format, source: impl Into<WriteOneError>) -> Self {
+        let source = source.into();
+        Self {
+            path: path.into(),
+            format,
+            operation: source.operation(),
+            source,
+        }
+    }
+}
+
+impl Display for SaveOneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_path_error(f, self.operation, &self.path, self.format, &self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde_json")]
+    use super::*;
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_display_path_format_and_operation() {
+        let error = SaveOneError::new("config.json", Format::Json, std::io::Error::new(std::io::ErrorKind::PermissionDenied, "permission denied"));
+        assert_eq!(error.to_string(), "Could not save config.json as Json: permission denied");
+        let source = Format::Json
+            .serialize_one(&std::collections::BTreeMap::from([((1, 2), 3)]))
+            .unwrap_err();
+        let error = SaveOneError::new("config.json", Format::Json, source);
+        assert_eq!(error.to_string(), "Could not serialize config.json as Json: key must be a string");
+    }
 }
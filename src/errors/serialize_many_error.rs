@@ -1,3 +1,4 @@
+use crate::errors::canonicalize_error::CanonicalizeError;
 use crate::errors::unsupported_format_error::UnsupportedFormatError;
 use derive_more::{Display, Error, From};
 
@@ -5,10 +6,29 @@ use derive_more::{Display, Error, From};
 #[non_exhaustive]
 pub enum SerializeManyError {
     UnsupportedFormat(UnsupportedFormatError),
+    #[from(ignore)]
+    Io(std::io::Error),
+    Canonicalize(CanonicalizeError),
+    #[cfg(feature = "serde_json")]
+    SerdeJson(serde_json::Error),
     #[cfg(feature = "serde-jsonlines")]
     SerdeJsonlines(std::io::Error),
+    #[cfg(feature = "serde_yaml")]
+    SerdeYaml(serde_yaml::Error),
+    #[cfg(feature = "quick-xml")]
+    QuickXml(quick_xml::SeError),
+    #[cfg(feature = "toml")]
+    Toml(toml::ser::Error),
     #[cfg(feature = "csv")]
     Csv(csv::Error),
-    #[cfg(feature = "csv")]
-    FromUtf8(std::string::FromUtf8Error),
+    #[cfg(feature = "ron")]
+    Ron(ron::Error),
+    #[cfg(feature = "ciborium")]
+    Ciborium(ciborium::ser::Error<std::io::Error>),
+    #[cfg(feature = "rmp-serde")]
+    RmpSerde(rmp_serde::encode::Error),
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::error::EncodeError),
+    #[cfg(feature = "postcard")]
+    Postcard(postcard::Error),
 }
@@ -1,8 +1,14 @@
+use crate::errors::binary_format_error::BinaryFormatError;
+use crate::errors::canonicalize_error::CanonicalizeError;
 use derive_more::{Display, Error, From};
 
 #[derive(Error, Display, From, Debug)]
 #[non_exhaustive]
 pub enum SerializeOneError {
+    #[from(ignore)]
+    Io(std::io::Error),
+    Canonicalize(CanonicalizeError),
+    BinaryFormat(BinaryFormatError),
     #[cfg(feature = "serde_json")]
     SerdeJson(serde_json::Error),
     #[cfg(feature = "serde-jsonlines")]
@@ -17,6 +23,16 @@ pub enum SerializeOneError {
     Toml(toml::ser::Error),
     #[cfg(feature = "csv")]
     Csv(csv::Error),
-    #[cfg(feature = "csv")]
-    FromUtf8(std::string::FromUtf8Error),
+    #[cfg(feature = "ron")]
+    Ron(ron::Error),
+    #[cfg(feature = "string-tree")]
+    StringTree(crate::errors::string_tree_error::StringTreeError),
+    #[cfg(feature = "ciborium")]
+    Ciborium(ciborium::ser::Error<std::io::Error>),
+    #[cfg(feature = "rmp-serde")]
+    RmpSerde(rmp_serde::encode::Error),
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::error::EncodeError),
+    #[cfg(feature = "postcard")]
+    Postcard(postcard::Error),
 }
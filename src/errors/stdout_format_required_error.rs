@@ -0,0 +1,11 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct StdoutFormatRequiredError;
+
+impl Display for StdoutFormatRequiredError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not select the output format for stdout: pass it with `--to`")
+    }
+}
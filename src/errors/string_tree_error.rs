@@ -0,0 +1,41 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct StringTreeError {
+    pub message: String,
+    /// The one-based line of the key that failed to deserialize, `None` if the tree was not parsed
+    pub line: Option<usize>,
+}
+
+impl StringTreeError {
+    /// Sets the line if the error has none (the innermost key is the most precise)
+    pub fn with_line(mut self, line: Option<usize>) -> Self {
+        self.line = self.line.or(line);
+        self
+    }
+}
+
+impl Display for StringTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl serde::ser::Error for StringTreeError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            message: message.to_string(),
+            line: None,
+        }
+    }
+}
+
+impl serde::de::Error for StringTreeError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            message: message.to_string(),
+            line: None,
+        }
+    }
+}
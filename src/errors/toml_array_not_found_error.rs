@@ -0,0 +1,13 @@
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct TomlArrayNotFoundError {
+    pub key: String,
+}
+
+impl Display for TomlArrayNotFoundError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TOML document has no array under the key {:?}", self.key)
+    }
+}
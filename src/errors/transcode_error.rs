@@ -0,0 +1,12 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::lossy_value_error::LossyValueError;
+use crate::errors::serialize_one_error::SerializeOneError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+#[non_exhaustive]
+pub enum TranscodeError {
+    Deserialize(DeserializeOneError),
+    Serialize(SerializeOneError),
+    Lossy(LossyValueError),
+}
@@ -0,0 +1,14 @@
+use crate::errors::deserialize_many_error::DeserializeManyError;
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::lossy_value_error::LossyValueError;
+use crate::errors::serialize_many_error::SerializeManyError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+#[non_exhaustive]
+pub enum TranscodeManyError {
+    DeserializeMany(DeserializeManyError),
+    Deserialize(DeserializeOneError),
+    Serialize(SerializeManyError),
+    Lossy(LossyValueError),
+}
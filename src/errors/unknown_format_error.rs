@@ -0,0 +1,42 @@
+use crate::errors::try_from_path_error::TryFromPathError;
+use crate::operation::Operation;
+use derive_more::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// The format could not be selected from the extension of the path (the error of the `*_as` methods)
+#[derive(Error, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct UnknownFormatError {
+    pub path: PathBuf,
+    pub operation: Operation,
+    pub source: TryFromPathError,
+}
+
+impl UnknownFormatError {
+    pub fn new(path: impl Into<PathBuf>, operation: Operation, source: TryFromPathError) -> Self {
+        Self {
+            path: path.into(),
+            operation,
+            source,
+        }
+    }
+}
+
+impl Display for UnknownFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not {} {}: {}", self.operation, self.path.display(), self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::Format;
+
+    #[test]
+    fn must_display_path_and_operation() {
+        let error = Format::load_one_as::<String>("data.xyz").unwrap_err();
+        assert_eq!(error.to_string(), "Could not load data.xyz: Unrecognized extension: \"xyz\"");
+        let error = Format::save_one_as("data", &"a").unwrap_err();
+        assert_eq!(error.to_string(), "Could not save data: Path has no extension: data");
+    }
+}
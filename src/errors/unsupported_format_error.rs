@@ -1,7 +1,14 @@
 use crate::format::Format;
-use derive_more::{Display, Error, From};
+use derive_more::{Error, From};
+use std::fmt::{Display, Formatter};
 
-#[derive(Error, Display, From, Debug, Copy, Clone)]
+#[derive(Error, From, Debug, Copy, Clone)]
 pub struct UnsupportedFormatError {
     pub format: Format,
 }
+
+impl Display for UnsupportedFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not supported by this operation", self.format)
+    }
+}
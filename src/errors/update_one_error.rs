@@ -0,0 +1,11 @@
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::lock_error::LockError;
+use crate::errors::save_one_error::SaveOneError;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum UpdateOneError {
+    Lock(LockError),
+    Load(LoadOneError),
+    Save(SaveOneError),
+}
@@ -0,0 +1,18 @@
+use crate::errors::serialize_one_error::SerializeOneError;
+use crate::operation::Operation;
+use derive_more::{Display, Error, From};
+
+#[derive(Error, Display, From, Debug)]
+pub enum WriteOneError {
+    Io(std::io::Error),
+    SerializeOne(SerializeOneError),
+}
+
+impl WriteOneError {
+    pub fn operation(&self) -> Operation {
+        match self {
+            WriteOneError::Io(_) => Operation::Save,
+            WriteOneError::SerializeOne(_) => Operation::Serialize,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::format::Format;
+use crate::operation::Operation;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// Writes `Could not {operation} {path} as {format}: {source}`, the common rendering of the errors that have a path (e.g. `Could not load config.json as Json: No such file or directory (os error 2)`)
+pub(crate) fn write_path_error(f: &mut Formatter<'_>, operation: Operation, path: &Path, format: Format, source: &dyn Display) -> std::fmt::Result {
+    write!(f, "Could not {operation} {} as {format}: {source}", path.display())
+}
+
+/// Writes the deserialization errors with the line and the column after the path (e.g. `Could not deserialize config.yaml:14:7 as Yaml: servers[2].port: invalid digit found in string`)
+///
+/// The errors without a line keep their location in the message (e.g. `byte 12: ...`).
+pub(crate) fn write_deserialize_error(f: &mut Formatter<'_>, operation: Operation, path: &Path, format: Format, error: &DeserializeOneError) -> std::fmt::Result {
+    match error.location() {
+        Some(location) if location.line.is_some() => write!(f, "Could not {operation} {}:{location} as {format}: {}", path.display(), error.message()),
+        _ => write_path_error(f, operation, path, format, error),
+    }
+}
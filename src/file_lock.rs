@@ -0,0 +1,129 @@
+use crate::errors::lock_error::LockError;
+use crate::errors::lock_timeout_error::LockTimeoutError;
+use crate::lock_options::LockOptions;
+use fs4::{FileExt, TryLockError};
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// An advisory lock on the `.lock` sidecar of a file (e.g. `state.json.lock` for `state.json`), released on drop
+///
+/// The lock is taken on the sidecar because the atomic saves replace the file itself (a lock on the replaced file would not exclude the next process).
+/// The sidecar is not removed on release, because a process that opened it before the removal would lock a different file than the next process.
+/// The lock is advisory: it excludes only the processes that take it too.
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Takes the lock that excludes all other locks (for writing)
+    pub fn exclusive(path: impl AsRef<Path>, options: &LockOptions) -> Result<Self, LockError> {
+        Self::acquire(path.as_ref(), options, FileExt::try_lock)
+    }
+
+    /// Takes the lock that excludes only the exclusive locks (for reading)
+    pub fn shared(path: impl AsRef<Path>, options: &LockOptions) -> Result<Self, LockError> {
+        Self::acquire(path.as_ref(), options, FileExt::try_lock_shared)
+    }
+
+    pub fn sidecar_path(path: impl AsRef<Path>) -> PathBuf {
+        let mut sidecar_path = OsString::from(path.as_ref());
+        sidecar_path.push(".lock");
+        sidecar_path.into()
+    }
+
+    /// Returns the path of the sidecar
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn acquire(path: &Path, options: &LockOptions, try_lock: fn(&File) -> Result<(), TryLockError>) -> Result<Self, LockError> {
+        let path = Self::sidecar_path(path);
+        let file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(source) => return Err(LockError::new(path, source)),
+        };
+        let started_at = Instant::now();
+        loop {
+            match try_lock(&file) {
+                Ok(()) => {
+                    return Ok(Self {
+                        file,
+                        path,
+                    });
+                }
+                Err(TryLockError::Error(source)) => return Err(LockError::new(path, source)),
+                Err(TryLockError::WouldBlock) => {}
+            }
+            if let Some(timeout) = options.timeout {
+                if started_at.elapsed() >= timeout {
+                    return Err(LockError::new(
+                        path,
+                        LockTimeoutError {
+                            timeout,
+                        },
+                    ));
+                }
+            }
+            std::thread::sleep(options.poll_interval);
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock anyway, so the error is ignored
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::lock_error_kind::LockErrorKind;
+    use std::time::Duration;
+
+    fn options(timeout: Duration) -> LockOptions {
+        LockOptions {
+            timeout: Some(timeout),
+            poll_interval: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn must_time_out_while_exclusive_lock_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let lock = FileLock::exclusive(&path, &options(Duration::ZERO)).unwrap();
+        assert_eq!(lock.path(), dir.path().join("state.json.lock"));
+        let error = FileLock::shared(&path, &options(Duration::from_millis(30))).unwrap_err();
+        assert_eq!(error.path, lock.path());
+        assert!(matches!(error.source, LockErrorKind::Timeout(LockTimeoutError { timeout }) if timeout == Duration::from_millis(30)));
+        drop(lock);
+        FileLock::exclusive(&path, &options(Duration::ZERO)).unwrap();
+    }
+
+    #[test]
+    fn must_share_shared_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let first = FileLock::shared(&path, &options(Duration::ZERO)).unwrap();
+        let _second = FileLock::shared(&path, &options(Duration::ZERO)).unwrap();
+        assert!(matches!(
+            FileLock::exclusive(&path, &options(Duration::ZERO))
+                .unwrap_err()
+                .source,
+            LockErrorKind::Timeout(_)
+        ));
+        drop(first);
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// When [`ManyWriter`](crate::many_writer::ManyWriter) flushes its buffer to the file
+#[derive(Serialize, Deserialize, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum FlushPolicy {
+    /// Flush when the buffer is full and on [`ManyWriter::finish`](crate::many_writer::ManyWriter::finish)
+    #[default]
+    Buffered,
+    /// Flush after every item, so that the readers of the file see each item as soon as it is pushed
+    EveryItem,
+    /// Flush after the given number of items
+    EveryItems(usize),
+}
+
+impl FlushPolicy {
+    pub fn is_due(self, unflushed_count: usize) -> bool {
+        match self {
+            FlushPolicy::Buffered => false,
+            FlushPolicy::EveryItem => true,
+            FlushPolicy::EveryItems(count) => unflushed_count >= count,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::cli_io::{input_format, is_many, is_stdio, read_input, write_output};
+use crate::errors::cli_error::CliError;
+use crate::errors::data_not_preserved_error::DataNotPreservedError;
+use crate::errors::load_many_error::LoadManyError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::save_one_error::SaveOneError;
+use crate::errors::transcode_error::TranscodeError;
+use crate::errors::transcode_many_error::TranscodeManyError;
+use crate::errors::unsupported_format_error::UnsupportedFormatError;
+use crate::format::Format;
+use crate::format_options::FormatOptions;
+use crate::many_options::ManyOptions;
+use crate::transcode::{read_transcode_value, read_transcode_values};
+use clap::Args;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Clone, Debug)]
+pub struct FmtCommand {
+    /// The files to rewrite in place (`-` reads stdin and writes to stdout)
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// The format of the files (detected from the extension or from the content by default)
+    #[arg(long)]
+    pub format: Option<Format>,
+    /// Format the items one by one (e.g. YAML documents), implied for JSON Lines
+    #[arg(long)]
+    pub many: bool,
+}
+
+impl FmtCommand {
+    /// Stops at the first file that can't be formatted (the files before it are already rewritten)
+    pub fn run(self) -> Result<(), CliError> {
+        self.paths.iter().try_for_each(|path| self.fmt_file(path))
+    }
+
+    /// The file is rewritten only if its content changes and the output reads back as the same data
+    fn fmt_file(&self, path: &Path) -> Result<(), CliError> {
+        let input = read_input(path)?;
+        let format = input_format(self.format, path, &input)?;
+        if !has_no_comments(format) {
+            return Err(UnsupportedFormatError {
+                format,
+            }
+            .into());
+        }
+        let not_preserved = || DataNotPreservedError {
+            path: path.into(),
+            format,
+        };
+        if has_markup_not_kept(format, &input) {
+            return Err(not_preserved().into());
+        }
+        // The canonical JSON has no trailing newline, but the files usually end with one
+        let format_options = FormatOptions {
+            trailing_newline: true,
+            ..FormatOptions::canonical()
+        };
+        let mut output = Vec::new();
+        let is_same_data = if is_many(format, self.many) {
+            let options = ManyOptions {
+                format: format_options,
+                ..ManyOptions::default()
+            };
+            Format::transcode_many_with(format, format, Cursor::new(input.clone()), &mut output, &options).map_err(|error| match error {
+                TranscodeManyError::Deserialize(error) => LoadOneError::from_input(path, format, error, &input).into(),
+                TranscodeManyError::DeserializeMany(error) => LoadManyError::new(path, format, error).into(),
+                error => CliError::from(error),
+            })?;
+            let read = |bytes: &[u8]| {
+                read_transcode_values(format, Cursor::new(bytes.to_vec()), &options)
+                    .ok()?
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+            };
+            matches!((read(&input), read(&output)), (Some(left), Some(right)) if left.len() == right.len() && left.iter().zip(&right).all(|(left, right)| left.is_same_data(right)))
+        } else {
+            Format::transcode_with(format, format, input.as_slice(), &mut output, &format_options).map_err(|error| match error {
+                TranscodeError::Deserialize(error) => LoadOneError::from_input(path, format, error, &input).into(),
+                error => CliError::from(error),
+            })?;
+            let read = |bytes: &[u8]| read_transcode_value(format, bytes, &format_options).ok();
+            matches!((read(&input), read(&output)), (Some(left), Some(right)) if left.is_same_data(&right))
+        };
+        if !is_same_data {
+            return Err(not_preserved().into());
+        }
+        if is_stdio(path) || output != input {
+            write_output(path, &output).map_err(|source| SaveOneError::new(path, format, source))?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the format has no comments (the comments are not part of the value, so rewriting a file would delete them)
+///
+/// XML is supported, but the files with comments are refused (see [`has_markup_not_kept`]). RON is refused too, because its untyped values lose the enum variants and the struct names.
+fn has_no_comments(format: Format) -> bool {
+    matches!(format, Format::Json | Format::Jsonl | Format::Xml | Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv)
+}
+
+/// Returns `true` if the XML input has comments, processing instructions or a doctype declaration, which the value doesn't keep
+fn has_markup_not_kept(format: Format, input: &[u8]) -> bool {
+    use quick_xml::events::Event;
+    if format != Format::Xml {
+        return false;
+    }
+    let mut reader = quick_xml::Reader::from_reader(input);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Comment(_) | Event::PI(_) | Event::DocType(_)) => return true,
+            Ok(Event::Eof) | Err(_) => return false,
+            Ok(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(path: &Path) -> Result<(), CliError> {
+        FmtCommand {
+            paths: vec![path.into()],
+            format: None,
+            many: false,
+        }
+        .fmt_file(path)
+    }
+
+    /// Formats the file twice and checks that it keeps the expected content
+    fn assert_fmt(file_name: &str, input: &str, expected: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file_name);
+        std::fs::write(&path, input).unwrap();
+        for _ in 0..2 {
+            fmt(&path).unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected, "{file_name}");
+        }
+    }
+
+    fn assert_refused(file_name: &str, input: &str, is_expected: impl Fn(&CliError) -> bool) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file_name);
+        std::fs::write(&path, input).unwrap();
+        let error = fmt(&path).unwrap_err();
+        assert!(is_expected(&error), "{file_name}: {error}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), input, "{file_name}");
+    }
+
+    #[test]
+    fn must_rewrite_in_canonical_style() {
+        assert_fmt("config.json", "{\n  \"b\": [1, 2.5],\n  \"a\": 2\n}", "{\"a\":2,\"b\":[1,2.5]}\n");
+        assert_fmt("events.jsonl", "{\"b\":1,\"a\":\"x\"}\n{\"b\":2,\"a\":\"y\"}\n", "{\"a\":\"x\",\"b\":1}\n{\"a\":\"y\",\"b\":2}\n");
+        assert_fmt("config.xml", "<config>\n  <port>80</port>\n  <name>a</name>\n</config>\n", "<config><name>a</name><port>80</port></config>\n");
+    }
+
+    #[test]
+    fn must_keep_canonical_files_byte_stable() {
+        let assert_stable = |file_name: &str, input: &str| assert_fmt(file_name, input, input);
+        assert_stable("config.json", "{\"a\":[true,null],\"b\":1,\"id\":9007199254740991}\n");
+        assert_stable("events.jsonl", "{\"a\":\"x\",\"b\":1}\n{\"a\":\"y\",\"b\":2}\n");
+        assert_stable("config.xml", "<config><name>a</name><port>80</port></config>\n");
+        assert_stable("rows.csv", "id,name\n1,a\n2,b\n");
+        assert_stable("rows.tsv", "id\tname\n1\ta\n");
+    }
+
+    #[test]
+    fn must_leave_file_unchanged_if_data_is_not_preserved() {
+        let is_not_preserved = |error: &CliError| matches!(error, CliError::DataNotPreserved(_));
+        // JCS writes the numbers as doubles, so the integers beyond 2^53 would be rounded
+        assert_refused("config.json", "{\"id\": 9007199254740993}", is_not_preserved);
+        assert_refused("config.json", "{\"id\": 18446744073709551616}", is_not_preserved);
+        assert_refused("config.xml", "<config>\n  <!-- the port of the server -->\n  <port>80</port>\n</config>\n", is_not_preserved);
+    }
+
+    #[test]
+    fn must_refuse_formats_with_comments() {
+        let is_unsupported = |error: &CliError| matches!(error, CliError::UnsupportedFormat(_));
+        assert_refused("config.yaml", "# server settings\nport: 80\n", is_unsupported);
+        assert_refused("config.toml", "# server settings\nport = 80\n", is_unsupported);
+        #[cfg(feature = "json5")]
+        assert_refused("config.jsonc", "// comment\n{\"port\": /* x */ 80}\n", is_unsupported);
+        #[cfg(feature = "rust-ini")]
+        assert_refused("config.ini", "; server settings\n[server]\nport=80\n", is_unsupported);
+    }
+}
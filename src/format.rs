@@ -1,21 +1,38 @@
+use crate::canonical_value::CanonicalValue;
+use crate::compression::{Compression, open_decompressed, read_decompressed};
+use crate::errors::canonicalize_error::CanonicalizeError;
 use crate::errors::deserialize_many_error::DeserializeManyError;
 use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::format_not_detected_error::FormatNotDetectedError;
 use crate::errors::load_many_as_error::LoadManyAsError;
 use crate::errors::load_many_error::LoadManyError;
 use crate::errors::load_one_as_error::LoadOneAsError;
+use crate::errors::load_one_auto_error::LoadOneAutoError;
 use crate::errors::load_one_error::LoadOneError;
 use crate::errors::path_has_no_extension_error::PathHasNoExtensionError;
+use crate::errors::read_file_error::ReadFileError;
 use crate::errors::save_one_as_error::SaveOneAsError;
 use crate::errors::save_one_error::SaveOneError;
 use crate::errors::serialize_many_error::SerializeManyError;
 use crate::errors::serialize_one_error::SerializeOneError;
 use crate::errors::try_from_path_error::TryFromPathError;
+use crate::errors::unknown_format_error::UnknownFormatError;
 use crate::errors::unrecognized_extension_error::UnrecognizedExtensionError;
+use crate::errors::write_one_error::WriteOneError;
+use crate::format_guess::FormatGuess;
+use crate::format_options::FormatOptions;
+use crate::last_byte_writer::LastByteWriter;
+use crate::many_options::ManyOptions;
+use crate::operation::Operation;
+use crate::save_mode::SaveMode;
+use crate::save_one_options::SaveOneOptions;
+use crate::string_writer::StringWriter;
+use crate::write_atomically::write_atomically;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
-use std::fs::{File, read_to_string};
-use std::io::{BufRead, BufReader, Write};
+use std::fs::{File, read};
+use std::io::{BufRead, BufWriter, Read, Write, stderr, stdout};
 use std::path::Path;
 use strum::{Display, VariantArray};
 
@@ -27,6 +44,16 @@ pub enum Format {
     Json,
     #[cfg(feature = "serde-jsonlines")]
     Jsonl,
+    /// JSON with comments and trailing commas on input, strict JSON on output
+    ///
+    /// The input is read by the JSON5 parser, so it may also have unquoted keys and single-quoted strings (e.g. `{name: 'a', port: 1}`), and the many items are read eagerly (see [`Format::deserialize_many_from_reader_with`]).
+    #[cfg(feature = "json5")]
+    Jsonc,
+    /// JSON5 (comments, trailing commas, unquoted keys, single-quoted strings, etc.) on input, strict JSON on output
+    ///
+    /// The many items are read eagerly (see [`Format::deserialize_many_from_reader_with`]).
+    #[cfg(feature = "json5")]
+    Json5,
     #[cfg(feature = "serde_yaml")]
     Yaml,
     #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
@@ -35,240 +62,975 @@ pub enum Format {
     Toml,
     #[cfg(feature = "csv")]
     Csv,
+    /// Tab-separated values
+    #[cfg(feature = "csv")]
+    Tsv,
+    /// Pipe-separated values
+    #[cfg(feature = "csv")]
+    Psv,
+    /// Semicolon-separated values (written by Excel in locales that use the comma as the decimal separator)
+    ///
+    /// Saved with the `.csv` extension; the methods that select the format by the extension read it back (see [`Format::resolve_csv_dialect`]).
+    #[cfg(feature = "csv")]
+    SemicolonCsv,
+    /// Binary (see [`Format::is_binary`])
+    #[cfg(feature = "ciborium")]
+    Cbor,
+    /// Binary (see [`Format::is_binary`]), the structs are written as maps
+    #[cfg(feature = "rmp-serde")]
+    MessagePack,
+    /// Binary (see [`Format::is_binary`]), prefixed with [`Format::binary_header`], the many items are length-prefixed (see [`write_length_prefixed`](crate::length_prefix::write_length_prefixed))
+    ///
+    /// The format is not self-describing, so it can't be transcoded or deserialized into untyped values.
+    #[cfg(feature = "bincode")]
+    Bincode,
+    /// Binary (see [`Format::is_binary`]), prefixed with [`Format::binary_header`], the many items are length-prefixed (see [`write_length_prefixed`](crate::length_prefix::write_length_prefixed))
+    ///
+    /// The format is not self-describing, so it can't be transcoded or deserialized into untyped values.
+    #[cfg(feature = "postcard")]
+    Postcard,
+    /// Rusty Object Notation (round-trips enums, tuples and `Option`), the many items are written as a top-level sequence
+    ///
+    /// The many items are read eagerly: the whole sequence is deserialized before the first item is returned (see [`Format::deserialize_many_from_reader_with`]).
+    ///
+    /// Transcoding from RON goes through its self-describing mode, which drops the names of the structs and the enum variants (e.g. `Some(B(7))` becomes `[7]`).
+    #[cfg(feature = "ron")]
+    Ron,
+    /// The sections and the dotted keys are nested structs or maps (e.g. `[server.tls]`), the sequences have indexed keys (e.g. `tags.0=a`), all values are strings parsed on demand
+    ///
+    /// The top level must be a struct or a map, and the many items are not supported.
+    #[cfg(feature = "rust-ini")]
+    Ini,
+    /// Java `.properties`: the dotted keys are nested structs or maps (e.g. `server.port=80`), the sequences have indexed keys (e.g. `tags.0=a`), all values are strings parsed on demand
+    ///
+    /// The top level must be a struct or a map, and the many items are not supported.
+    #[cfg(feature = "properties")]
+    Properties,
 }
 
 impl Format {
+    /// Saves the value atomically (see [`SaveMode::Atomic`])
     pub fn save_one<T: Serialize>(self, path: impl AsRef<Path>, value: &T) -> Result<(), SaveOneError> {
-        let mut file = File::create(path)?;
-        let output = self.serialize_one(value)?;
-        file.write_all(output.as_bytes())?;
-        Ok(())
+        self.save_one_with(path, value, &SaveOneOptions::default())
+    }
+
+    pub fn save_one_with<T: Serialize>(self, path: impl AsRef<Path>, value: &T, options: &SaveOneOptions) -> Result<(), SaveOneError> {
+        let path = path.as_ref();
+        let compression = Compression::from_path(path);
+        let result = match options.mode {
+            SaveMode::Atomic => write_atomically(path, |file| self.write_one_maybe_compressed(file, value, options, compression)),
+            SaveMode::Truncate => File::create(path)
+                .map_err(WriteOneError::from)
+                .and_then(|mut file| self.write_one_maybe_compressed(&mut file, value, options, compression)),
+        };
+        result.map_err(|source| SaveOneError::new(path, self, source))
+    }
+
+    pub(crate) fn write_one_maybe_compressed<T: Serialize>(self, writer: &mut impl Write, value: &T, options: &SaveOneOptions, compression: Option<Compression>) -> Result<(), WriteOneError> {
+        match compression {
+            Some(compression) => compression.write_compressed(writer, options.compression_level, |mut writer| self.write_one_with(&mut writer, value, &options.format)),
+            None => self.write_one_with(writer, value, &options.format),
+        }
     }
 
     pub fn save_one_as<T: Serialize>(path: impl AsRef<Path>, value: &T) -> Result<(), SaveOneAsError> {
-        let format = Format::try_from_path(path.as_ref())?;
-        format.save_one(path, value).map_err(From::from)
+        Self::save_one_as_with(path, value, &SaveOneOptions::default())
+    }
+
+    pub fn save_one_as_with<T: Serialize>(path: impl AsRef<Path>, value: &T, options: &SaveOneOptions) -> Result<(), SaveOneAsError> {
+        let path = path.as_ref();
+        let format = Format::try_from_path(path).map_err(|source| UnknownFormatError::new(path, Operation::Save, source))?;
+        format
+            .save_one_with(path, value, options)
+            .map_err(From::from)
     }
 
     pub fn save_one_to<T: Serialize>(self, file_dir: impl AsRef<Path>, file_stem: &str, value: &T) -> Result<(), SaveOneError> {
+        self.save_one_to_with(file_dir, file_stem, value, &SaveOneOptions::default())
+    }
+
+    pub fn save_one_to_with<T: Serialize>(self, file_dir: impl AsRef<Path>, file_stem: &str, value: &T, options: &SaveOneOptions) -> Result<(), SaveOneError> {
         let path_buf = file_dir.as_ref().join(self.to_file_name(file_stem));
-        self.save_one(path_buf, value)
+        self.save_one_with(path_buf, value, options)
+    }
+
+    #[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+    pub fn append_many<T: Serialize>(self, path: impl AsRef<Path>) -> Result<crate::many_writer::ManyWriter<T>, crate::errors::append_many_error::AppendManyError> {
+        self.append_many_with(path, &crate::append_many_options::AppendManyOptions::default())
+    }
+
+    /// Opens the file for appending the items one by one (only JSON Lines and the CSV-like formats are supported, because the other formats can't be appended to without rewriting the file)
+    #[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+    pub fn append_many_with<T: Serialize>(self, path: impl AsRef<Path>, options: &crate::append_many_options::AppendManyOptions) -> Result<crate::many_writer::ManyWriter<T>, crate::errors::append_many_error::AppendManyError> {
+        crate::many_writer::ManyWriter::open(self, path, options)
     }
 
     pub fn load_one<T: DeserializeOwned>(self, path: impl AsRef<Path>) -> Result<T, LoadOneError> {
-        let string = read_to_string(path)?;
-        let output = self.deserialize_one(&string)?;
-        Ok(output)
+        self.load_one_with(path, &FormatOptions::default())
+    }
+
+    /// Loads the value with the options that apply to the input (the dialect of the CSV-like formats and the schema version of the compact Rust-native formats)
+    pub fn load_one_with<T: DeserializeOwned>(self, path: impl AsRef<Path>, options: &FormatOptions) -> Result<T, LoadOneError> {
+        self.load_one_from_file(path.as_ref(), options, |_, _| None)
     }
 
     pub fn load_many<T: DeserializeOwned + 'static>(self, path: impl AsRef<Path>) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, LoadManyError> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        let output = self.deserialize_many_from_reader(reader)?;
+        self.load_many_with(path, &ManyOptions::default())
+    }
+
+    pub fn load_many_with<T: DeserializeOwned + 'static>(self, path: impl AsRef<Path>, options: &ManyOptions) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, LoadManyError> {
+        let path = path.as_ref();
+        let reader = open_decompressed(path).map_err(|source| LoadManyError::new(path, self, source))?;
+        let output = self
+            .deserialize_many_from_reader_with(reader, options)
+            .map_err(|source| LoadManyError::new(path, self, source))?;
         Ok(output)
     }
 
+    /// Loads the value in the format from the extension (a `.csv` file may be in the semicolon dialect, see [`Format::resolve_csv_dialect`])
     pub fn load_one_as<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, LoadOneAsError> {
-        let format = Format::try_from_path(path.as_ref())?;
-        format.load_one(path).map_err(From::from)
+        let path = path.as_ref();
+        let format = Format::try_from_path(path).map_err(|source| UnknownFormatError::new(path, Operation::Load, source))?;
+        format.load_one_from_extension(path).map_err(From::from)
+    }
+
+    fn load_one_from_extension<T: DeserializeOwned>(self, path: &Path) -> Result<T, LoadOneError> {
+        self.load_one_from_file(path, &FormatOptions::default(), Format::resolve_csv_dialect)
+    }
+
+    /// Deserializes a compressed file while it is decompressed, so that the compressed and the decompressed input are never both in memory
+    ///
+    /// The `resolve` function picks the format from the start of the input (see [`Format::resolve_csv_dialect`]).
+    #[allow(unused_variables, unreachable_code)]
+    fn load_one_from_file<T: DeserializeOwned>(self, path: &Path, options: &FormatOptions, resolve: fn(Format, &[u8]) -> Option<Format>) -> Result<T, LoadOneError> {
+        if Compression::from_path(path).is_none() {
+            let bytes = read(path).map_err(|source| LoadOneError::new(path, self, source))?;
+            let format = resolve(self, &bytes).unwrap_or(self);
+            return format
+                .deserialize_one_from_slice_with(&bytes, options)
+                .map_err(|source| LoadOneError::from_input(path, format, source, &bytes));
+        }
+        let mut reader = open_decompressed(path).map_err(|source| LoadOneError::new(path, self, source))?;
+        let header = reader
+            .fill_buf()
+            .map_err(|source| LoadOneError::new(path, self, source))?;
+        let format = resolve(self, header).unwrap_or(self);
+        format
+            .deserialize_one_from_reader_with(reader, options)
+            .map_err(|source| match format.is_binary() {
+                true => LoadOneError::new(path, format, source),
+                // The input is decompressed again only to locate the error in it
+                false => match read_decompressed(path) {
+                    Ok(bytes) => LoadOneError::from_input(path, format, source.with_input(&bytes), &bytes),
+                    Err(_) => LoadOneError::new(path, format, source),
+                },
+            })
+    }
+
+    /// Loads the value in the format from the extension, or in the format detected from the content if the path has no extension or an unrecognized one (see [`Format::detect`])
+    ///
+    /// The detected formats are tried in the order of their scores; if all of them fail, the error of the best guess is returned.
+    pub fn load_one_auto<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, LoadOneAutoError> {
+        let path = path.as_ref();
+        if let Ok(format) = Format::try_from_path(path) {
+            return format.load_one_from_extension(path).map_err(From::from);
+        }
+        let bytes = read_decompressed(path).map_err(|source| ReadFileError {
+            path: path.into(),
+            source,
+        })?;
+        let mut first_error = None;
+        for guess in Format::detect(&bytes) {
+            match guess.format.deserialize_one_from_slice(&bytes) {
+                Ok(output) => return Ok(output),
+                Err(source) => {
+                    first_error.get_or_insert_with(|| LoadOneError::from_input(path, guess.format, source, &bytes));
+                }
+            }
+        }
+        match first_error {
+            Some(error) => Err(error.into()),
+            None => Err(FormatNotDetectedError {
+                path: path.into(),
+            }
+            .into()),
+        }
     }
 
     pub fn load_many_as<T: DeserializeOwned + 'static>(path: impl AsRef<Path>) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, LoadManyAsError> {
-        let format = Format::try_from_path(path.as_ref())?;
-        format.load_many(path).map_err(From::from)
+        Self::load_many_as_with(path, &ManyOptions::default())
+    }
+
+    /// Loads the items in the format from the extension (a `.csv` file may be in the semicolon dialect, see [`Format::resolve_csv_dialect`])
+    pub fn load_many_as_with<T: DeserializeOwned + 'static>(path: impl AsRef<Path>, options: &ManyOptions) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, LoadManyAsError> {
+        let path = path.as_ref();
+        let format = Format::try_from_path(path).map_err(|source| UnknownFormatError::new(path, Operation::Load, source))?;
+        let mut reader = open_decompressed(path).map_err(|source| LoadManyError::new(path, format, source))?;
+        let header = reader
+            .fill_buf()
+            .map_err(|source| LoadManyError::new(path, format, source))?;
+        let format = match format.resolve_csv_dialect(header) {
+            Some(format) => format,
+            None => format,
+        };
+        let output = format
+            .deserialize_many_from_reader_with(reader, options)
+            .map_err(|source| LoadManyError::new(path, format, source))?;
+        Ok(output)
     }
 
     pub fn print_one<T: Serialize>(self, input: &T) -> Result<(), SerializeOneError> {
-        let string = self.serialize_one(input)?;
-        print!("{string}");
-        Ok(())
+        self.serialize_one_to_buffered_writer(input, stdout().lock(), false)
     }
 
     pub fn eprint_one<T: Serialize>(self, input: &T) -> Result<(), SerializeOneError> {
-        let string = self.serialize_one(input)?;
-        eprint!("{string}");
-        Ok(())
+        self.serialize_one_to_buffered_writer(input, stderr().lock(), false)
     }
 
     pub fn println_one<T: Serialize>(self, input: &T) -> Result<(), SerializeOneError> {
-        let string = self.serialize_one(input)?;
-        println!("{string}");
-        Ok(())
+        self.serialize_one_to_buffered_writer(input, stdout().lock(), true)
     }
 
     pub fn eprintln_one<T: Serialize>(self, input: &T) -> Result<(), SerializeOneError> {
-        let string = self.serialize_one(input)?;
-        eprintln!("{string}");
-        Ok(())
+        self.serialize_one_to_buffered_writer(input, stderr().lock(), true)
+    }
+
+    pub fn write_one<T: Serialize>(self, writer: &mut impl Write, input: &T) -> Result<(), WriteOneError> {
+        self.write_one_with(writer, input, &FormatOptions::default())
     }
 
-    pub fn write_one<T: Serialize>(self, writer: &mut impl Write, input: &T) -> Result<(), SaveOneError> {
-        let string = self.serialize_one(input)?;
-        write!(writer, "{string}")?;
+    pub fn write_one_with<T: Serialize>(self, writer: &mut impl Write, input: &T, options: &FormatOptions) -> Result<(), WriteOneError> {
+        let mut writer = BufWriter::new(writer);
+        self.serialize_one_to_writer_with(input, &mut writer, options)?;
+        writer.flush()?;
         Ok(())
     }
 
-    pub fn writeln_one<T: Serialize>(self, writer: &mut impl Write, input: &T) -> Result<(), SaveOneError> {
-        let string = self.serialize_one(input)?;
-        writeln!(writer, "{string}")?;
+    pub fn writeln_one<T: Serialize>(self, writer: &mut impl Write, input: &T) -> Result<(), WriteOneError> {
+        self.ensure_text()?;
+        let mut writer = BufWriter::new(writer);
+        self.serialize_one_to_writer(input, &mut writer)?;
+        writeln!(writer)?;
+        writer.flush()?;
         Ok(())
     }
 
-    #[allow(unreachable_patterns, unused_variables, unreachable_code)]
+    fn serialize_one_to_buffered_writer<T: Serialize>(self, input: &T, writer: impl Write, newline: bool) -> Result<(), SerializeOneError> {
+        self.ensure_text()?;
+        let mut writer = BufWriter::new(writer);
+        self.serialize_one_to_writer(input, &mut writer)?;
+        if newline {
+            writeln!(writer).map_err(SerializeOneError::Io)?;
+        }
+        writer.flush().map_err(SerializeOneError::Io)
+    }
+
     pub fn serialize_one<T: Serialize>(self, input: &T) -> Result<String, SerializeOneError> {
-        Ok(match self {
+        self.serialize_one_with(input, &FormatOptions::default())
+    }
+
+    /// Returns [`BinaryFormatError`](crate::errors::binary_format_error::BinaryFormatError) for the binary formats (see [`Format::serialize_one_to_vec_with`])
+    ///
+    /// The output is written straight into the `String`; the bytes that are not UTF-8 (e.g. a CSV field serialized from raw bytes) return [`io::ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData).
+    pub fn serialize_one_with<T: Serialize>(self, input: &T, options: &FormatOptions) -> Result<String, SerializeOneError> {
+        self.ensure_text()?;
+        let mut writer = StringWriter::default();
+        self.serialize_one_to_writer_with(input, &mut writer, options)?;
+        writer.into_string().map_err(SerializeOneError::Io)
+    }
+
+    pub fn serialize_one_to_vec<T: Serialize>(self, input: &T) -> Result<Vec<u8>, SerializeOneError> {
+        self.serialize_one_to_vec_with(input, &FormatOptions::default())
+    }
+
+    /// Works for all formats, including the binary ones
+    pub fn serialize_one_to_vec_with<T: Serialize>(self, input: &T, options: &FormatOptions) -> Result<Vec<u8>, SerializeOneError> {
+        let mut buffer = Vec::new();
+        self.serialize_one_to_writer_with(input, &mut buffer, options)?;
+        Ok(buffer)
+    }
+
+    /// Serializes directly into the writer, without building an intermediate `String` (except for TOML, which has to render the whole document in memory before writing it)
+    ///
+    /// The writer is not flushed; wrap it in a [`BufWriter`] if it is unbuffered.
+    pub fn serialize_one_to_writer<T: Serialize>(self, input: &T, writer: &mut impl Write) -> Result<(), SerializeOneError> {
+        self.serialize_one_to_writer_with(input, writer, &FormatOptions::default())
+    }
+
+    pub fn serialize_one_to_writer_with<T: Serialize>(self, input: &T, writer: &mut impl Write, options: &FormatOptions) -> Result<(), SerializeOneError> {
+        let mut writer = LastByteWriter::new(writer);
+        if options.canonical {
+            let value = CanonicalValue::from_serialize(input)?;
+            self.serialize_canonical_one_to_writer(&value, &mut writer, options)?;
+        } else {
+            self.serialize_native_one_to_writer(input, &mut writer, options)?;
+        }
+        if options.trailing_newline && !self.is_binary() && writer.last_byte != Some(b'\n') {
+            writer.write_all(b"\n").map_err(SerializeOneError::Io)?;
+        }
+        Ok(())
+    }
+
+    #[allow(unreachable_patterns, unused_variables)]
+    fn serialize_canonical_one_to_writer(self, value: &CanonicalValue, writer: &mut impl Write, options: &FormatOptions) -> Result<(), SerializeOneError> {
+        match self {
             #[cfg(feature = "serde_json")]
-            Format::Json => serde_json::to_string_pretty(input)?,
+            Format::Json => write!(writer, "{}", crate::jcs::to_string(value)?).map_err(SerializeOneError::Io),
             #[cfg(feature = "serde-jsonlines")]
-            Format::Jsonl => {
-                let mut buffer = Vec::new();
-                let mut ser = serde_jsonlines::JsonLinesWriter::new(&mut buffer);
-                ser.write(input)
-                    .map_err(SerializeOneError::SerdeJsonlines)?;
-                String::from_utf8_lossy(&buffer).into_owned()
+            Format::Jsonl => writeln!(writer, "{}", crate::jcs::to_string(value)?).map_err(SerializeOneError::Io),
+            // The lenient JSON dialects write strict JSON
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => Format::Json.serialize_canonical_one_to_writer(value, writer, options),
+            _ => self.serialize_native_one_to_writer(value, writer, options),
+        }
+    }
+
+    #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
+    fn serialize_native_one_to_writer<T: Serialize>(self, input: &T, writer: &mut impl Write, options: &FormatOptions) -> Result<(), SerializeOneError> {
+        match self {
+            #[cfg(feature = "serde_json")]
+            Format::Json => {
+                if options.layout.is_pretty(true) {
+                    let indent = options.indent();
+                    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                    input.serialize(&mut serde_json::Serializer::with_formatter(writer, formatter))?
+                } else {
+                    serde_json::to_writer(writer, input)?
+                }
             }
+            #[cfg(feature = "serde-jsonlines")]
+            Format::Jsonl => serde_jsonlines::JsonLinesWriter::new(writer)
+                .write(input)
+                .map_err(SerializeOneError::SerdeJsonlines)?,
+            // The lenient JSON dialects write strict JSON
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => Format::Json.serialize_native_one_to_writer(input, writer, options)?,
             #[cfg(feature = "serde_yaml")]
-            Format::Yaml => serde_yaml::to_string(input)?,
+            Format::Yaml => serde_yaml::to_writer(writer, input)?,
             #[cfg(feature = "serde-xml-rs")]
-            Format::Xml => serde_xml_rs::to_string(input)?,
+            Format::Xml => serde_xml_rs::to_writer(writer, input)?,
             #[cfg(feature = "quick-xml")]
-            Format::Xml => quick_xml::se::to_string(input)?,
+            Format::Xml => {
+                if options.layout.is_pretty(false) {
+                    // The indenting serializer writes only into a `fmt::Write`
+                    let mut string = String::new();
+                    let mut serializer = quick_xml::se::Serializer::new(&mut string);
+                    serializer.indent(options.indent_char, options.indent_width);
+                    input.serialize(serializer)?;
+                    writer
+                        .write_all(string.as_bytes())
+                        .map_err(SerializeOneError::Io)?;
+                } else {
+                    quick_xml::se::to_utf8_io_writer(writer, input)?;
+                }
+            }
             #[cfg(feature = "toml")]
-            Format::Toml => toml::to_string(input)?,
+            Format::Toml => {
+                let string = if options.toml_pretty_arrays { toml::to_string_pretty(input)? } else { toml::to_string(input)? };
+                writer
+                    .write_all(string.as_bytes())
+                    .map_err(SerializeOneError::Io)?;
+            }
             #[cfg(feature = "csv")]
-            Format::Csv => {
-                let mut writer = csv::Writer::from_writer(vec![]);
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                let mut csv_options = options.csv.unwrap_or_else(|| self.csv_options());
+                if options.canonical {
+                    csv_options.terminator = crate::csv_terminator::CsvTerminator::Lf;
+                }
+                let mut writer = csv_options.writer_builder().from_writer(writer);
                 writer.serialize(input)?;
-                let vec = writer
-                    .into_inner()
-                    .expect("Writer must return a vec without errors");
-                String::from_utf8(vec)?
+                writer.flush().map_err(SerializeOneError::Io)?;
             }
-        })
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => ciborium::into_writer(input, writer)?,
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => rmp_serde::encode::write_named(writer, input)?,
+            #[cfg(feature = "bincode")]
+            Format::Bincode => {
+                crate::binary_header::BinaryHeader::BINCODE
+                    .with_schema_version(options.schema_version)
+                    .write(writer)
+                    .map_err(SerializeOneError::Io)?;
+                bincode::serde::encode_into_std_write(input, writer, crate::compact_codec::bincode_config())?;
+            }
+            #[cfg(feature = "postcard")]
+            Format::Postcard => {
+                crate::binary_header::BinaryHeader::POSTCARD
+                    .with_schema_version(options.schema_version)
+                    .write(writer)
+                    .map_err(SerializeOneError::Io)?;
+                postcard::to_io(input, writer)?;
+            }
+            #[cfg(feature = "ron")]
+            Format::Ron => match options.ron_pretty_config() {
+                Some(config) => ron::Options::default().to_io_writer_pretty(writer, input, config)?,
+                None => ron::Options::default().to_io_writer(writer, input)?,
+            },
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => crate::ini_tree::write(writer, &crate::string_tree_serializer::to_string_tree(input)?).map_err(SerializeOneError::Io)?,
+            #[cfg(feature = "properties")]
+            Format::Properties => crate::properties::write(writer, crate::string_tree_serializer::to_string_tree(input)?).map_err(SerializeOneError::Io)?,
+        }
+        Ok(())
     }
 
-    #[allow(unreachable_patterns, unused_variables, unreachable_code)]
     pub fn serialize_many_to_writer<'a, T: Serialize + 'a>(self, input: impl IntoIterator<Item = &'a T>, writer: &mut impl Write) -> Result<(), SerializeManyError> {
+        self.serialize_many_to_writer_with(input, writer, &ManyOptions::default())
+    }
+
+    pub fn serialize_many_to_writer_with<'a, T: Serialize + 'a>(self, input: impl IntoIterator<Item = &'a T>, writer: &mut impl Write, options: &ManyOptions) -> Result<(), SerializeManyError> {
+        self.serialize_many_items_to_writer_with(input, writer, options)
+    }
+
+    /// Accepts the items by value (e.g. the wrappers created while transcoding)
+    pub(crate) fn serialize_many_items_to_writer_with<I: Serialize>(self, input: impl IntoIterator<Item = I>, writer: &mut impl Write, options: &ManyOptions) -> Result<(), SerializeManyError> {
+        let mut writer = LastByteWriter::new(writer);
+        if options.format.canonical {
+            // The items are canonicalized one by one as they are written
+            let values = input
+                .into_iter()
+                .map(|item| CanonicalValue::from_serialize(&item));
+            self.serialize_canonical_many_to_writer(values, &mut writer, options)?;
+        } else {
+            self.serialize_native_many_to_writer(input, &mut writer, options)?;
+        }
+        if options.format.trailing_newline && !self.is_binary() && writer.last_byte != Some(b'\n') {
+            writer.write_all(b"\n").map_err(SerializeManyError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Stops at the first item that can't be canonicalized (the JSON array is left unclosed)
+    #[allow(unreachable_patterns, unused_variables)]
+    fn serialize_canonical_many_to_writer(self, values: impl Iterator<Item = Result<CanonicalValue, CanonicalizeError>>, writer: &mut impl Write, options: &ManyOptions) -> Result<(), SerializeManyError> {
+        match self {
+            #[cfg(feature = "serde_json")]
+            Format::Json => {
+                writer.write_all(b"[").map_err(SerializeManyError::Io)?;
+                for (index, value) in values.enumerate() {
+                    let separator = if index == 0 { "" } else { "," };
+                    write!(writer, "{separator}{}", crate::jcs::to_string(&value?)?).map_err(SerializeManyError::Io)?;
+                }
+                writer.write_all(b"]").map_err(SerializeManyError::Io)
+            }
+            #[cfg(feature = "serde-jsonlines")]
+            Format::Jsonl => {
+                for value in values {
+                    writeln!(writer, "{}", crate::jcs::to_string(&value?)?).map_err(SerializeManyError::Io)?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => Format::Json.serialize_canonical_many_to_writer(values, writer, options),
+            _ => {
+                // The native serializers accept only the items, so the iteration stops at the first error, which is returned after the serializer finishes
+                let mut error = None;
+                let values = values.map_while(|value| value.map_err(|source| error = Some(source)).ok());
+                self.serialize_native_many_to_writer(values, writer, options)?;
+                match error {
+                    Some(error) => Err(error.into()),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
+    fn serialize_native_many_to_writer<I: Serialize>(self, input: impl IntoIterator<Item = I>, writer: &mut impl Write, options: &ManyOptions) -> Result<(), SerializeManyError> {
         let items = input.into_iter();
+        let format_options = &options.format;
         match self {
             #[cfg(feature = "serde_json")]
-            Format::Json => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
+            Format::Json => {
+                // `collect_seq` streams the items into the array without collecting them first
+                if format_options.layout.is_pretty(true) {
+                    let indent = format_options.indent();
+                    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                    serde::Serializer::collect_seq(&mut serde_json::Serializer::with_formatter(writer, formatter), items)?;
+                } else {
+                    serde::Serializer::collect_seq(&mut serde_json::Serializer::new(writer), items)?;
+                }
+            }
             #[cfg(feature = "serde-jsonlines")]
             Format::Jsonl => {
                 let mut writer = serde_jsonlines::JsonLinesWriter::new(writer);
                 for item in items {
-                    writer.write(item)?;
+                    writer.write(&item)?;
                 }
-                Ok(())
             }
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => Format::Json.serialize_native_many_to_writer(items, writer, options)?,
             #[cfg(feature = "serde_yaml")]
-            Format::Yaml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
-            #[cfg(feature = "serde-xml-rs")]
-            Format::Xml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
+            Format::Yaml => {
+                // serde_yaml separates the documents with `---`
+                let mut serializer = serde_yaml::Serializer::new(writer);
+                for item in items {
+                    item.serialize(&mut serializer)?;
+                }
+                serializer.flush()?;
+            }
+            // The many-item XML support is built on quick-xml, so it takes precedence over serde-xml-rs here
             #[cfg(feature = "quick-xml")]
+            Format::Xml => {
+                let is_pretty = format_options.layout.is_pretty(false);
+                let separator = if is_pretty { "\n" } else { "" };
+                write!(writer, "<{}>", options.xml_root_tag).map_err(SerializeManyError::Io)?;
+                for item in items {
+                    let mut string = String::new();
+                    let mut serializer = quick_xml::se::Serializer::with_root(&mut string, Some(&options.xml_item_tag))?;
+                    if is_pretty {
+                        serializer.indent(format_options.indent_char, format_options.indent_width);
+                    }
+                    item.serialize(serializer)?;
+                    write!(writer, "{separator}{string}").map_err(SerializeManyError::Io)?;
+                }
+                write!(writer, "{separator}</{}>", options.xml_root_tag).map_err(SerializeManyError::Io)?;
+            }
+            #[cfg(all(feature = "serde-xml-rs", not(feature = "quick-xml")))]
             Format::Xml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
                 format: self,
             })?,
             #[cfg(feature = "toml")]
-            Format::Toml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
+            Format::Toml => {
+                // TOML has no top-level arrays, so the items are written as an array of tables under `options.toml_key`
+                let items = items.collect::<Vec<_>>();
+                let document = std::collections::BTreeMap::from([(options.toml_key.as_str(), items)]);
+                let string = if format_options.toml_pretty_arrays {
+                    toml::to_string_pretty(&document)?
+                } else {
+                    toml::to_string(&document)?
+                };
+                writer
+                    .write_all(string.as_bytes())
+                    .map_err(SerializeManyError::Io)?;
+            }
             #[cfg(feature = "csv")]
-            Format::Csv => {
-                let mut writer = csv::Writer::from_writer(writer);
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                let mut csv_options = format_options.csv.unwrap_or_else(|| self.csv_options());
+                if format_options.canonical {
+                    csv_options.terminator = crate::csv_terminator::CsvTerminator::Lf;
+                }
+                let mut writer = csv_options.writer_builder().from_writer(writer);
                 for item in items {
                     writer.serialize(item)?;
                 }
-                Ok(())
+                writer.flush().map_err(SerializeManyError::Io)?;
+            }
+            // The binary items are self-delimiting, so they are written back to back (e.g. a CBOR sequence)
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => {
+                for item in items {
+                    ciborium::into_writer(&item, &mut *writer)?;
+                }
+            }
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => {
+                for item in items {
+                    rmp_serde::encode::write_named(writer, &item)?;
+                }
+            }
+            // The compact items are not self-delimiting, so each one is prefixed with its length
+            #[cfg(feature = "bincode")]
+            Format::Bincode => {
+                crate::binary_header::BinaryHeader::BINCODE
+                    .with_schema_version(format_options.schema_version)
+                    .write(writer)
+                    .map_err(SerializeManyError::Io)?;
+                for item in items {
+                    let item = bincode::serde::encode_to_vec(&item, crate::compact_codec::bincode_config())?;
+                    crate::length_prefix::write_length_prefixed(writer, &item).map_err(SerializeManyError::Io)?;
+                }
             }
+            #[cfg(feature = "postcard")]
+            Format::Postcard => {
+                crate::binary_header::BinaryHeader::POSTCARD
+                    .with_schema_version(format_options.schema_version)
+                    .write(writer)
+                    .map_err(SerializeManyError::Io)?;
+                for item in items {
+                    let item = postcard::to_stdvec(&item)?;
+                    crate::length_prefix::write_length_prefixed(writer, &item).map_err(SerializeManyError::Io)?;
+                }
+            }
+            #[cfg(feature = "ron")]
+            Format::Ron => {
+                // The RON serializer writes to a `fmt::Write`, so the sequence is rendered in memory first
+                let mut string = String::new();
+                let mut serializer = ron::Serializer::new(&mut string, format_options.ron_pretty_config())?;
+                serde::Serializer::collect_seq(&mut serializer, items)?;
+                writer
+                    .write_all(string.as_bytes())
+                    .map_err(SerializeManyError::Io)?;
+            }
+            // The documents of the key-value formats have no delimiters, so they can't hold many items
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
+                format: self,
+            })?,
+            #[cfg(feature = "properties")]
+            Format::Properties => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
+                format: self,
+            })?,
         }
+        Ok(())
     }
 
-    #[allow(unreachable_patterns, unused_variables, unreachable_code)]
     pub fn deserialize_one<T: DeserializeOwned>(self, input: &str) -> Result<T, DeserializeOneError> {
+        self.deserialize_one_from_slice(input.as_bytes())
+    }
+
+    /// Deserializes from bytes (each backend decodes them as needed, so there is no upfront UTF-8 check)
+    ///
+    /// The errors contain the path to the field that failed to deserialize (see [`DeserializeOneError::field_path`]) and, for the text formats, the line and the column (see [`DeserializeOneError::location`]).
+    pub fn deserialize_one_from_slice<T: DeserializeOwned>(self, input: &[u8]) -> Result<T, DeserializeOneError> {
+        self.deserialize_one_from_slice_with(input, &FormatOptions::default())
+    }
+
+    /// Deserializes from bytes with the options that apply to the input (the dialect of the CSV-like formats)
+    pub fn deserialize_one_from_slice_with<T: DeserializeOwned>(self, input: &[u8], options: &FormatOptions) -> Result<T, DeserializeOneError> {
+        self.deserialize_one_from_slice_unlocated(input, options)
+            .map_err(|error| match self.is_binary() {
+                true => error,
+                false => error.with_input(input),
+            })
+    }
+
+    /// Returns the errors with the locations that the backends report (some of them report only the byte offset)
+    #[allow(unreachable_patterns, unused_variables, unreachable_code)]
+    fn deserialize_one_from_slice_unlocated<T: DeserializeOwned>(self, input: &[u8], options: &FormatOptions) -> Result<T, DeserializeOneError> {
         Ok(match self {
             #[cfg(feature = "serde_json")]
-            Format::Json => serde_json::from_str(input)?,
+            Format::Json => {
+                // serde_json counts the column in bytes
+                let mut deserializer = serde_json::Deserializer::from_slice(input);
+                let output = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| DeserializeOneError::from_path_to_error(error).with_byte_column(input))?;
+                deserializer
+                    .end()
+                    .map_err(|error| DeserializeOneError::from(error).with_byte_column(input))?;
+                output
+            }
             #[cfg(feature = "serde-jsonlines")]
             Format::Jsonl => {
-                let mut reader = serde_jsonlines::JsonLinesReader::new(input.as_bytes());
+                let mut reader = serde_jsonlines::JsonLinesReader::new(input);
                 reader
                     .read()
-                    .map_err(DeserializeOneError::SerdeJsonlines)?
-                    .ok_or::<DeserializeOneError>(crate::errors::item_not_found_error::ItemNotFoundError.into())?
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::SerdeJsonlines)?
+                    .ok_or(crate::errors::item_not_found_error::ItemNotFoundError)?
+            }
+            // JSON5 is a superset of JSONC, so the same parser reads both
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => {
+                let string = std::str::from_utf8(input).map_err(DeserializeOneError::from)?;
+                let mut deserializer = json5::Deserializer::from_str(string)?;
+                serde_path_to_error::deserialize(&mut deserializer).map_err(DeserializeOneError::from_path_to_error)?
             }
             #[cfg(feature = "serde_yaml")]
-            Format::Yaml => serde_yaml::from_str(input)?,
+            Format::Yaml => serde_path_to_error::deserialize(serde_yaml::Deserializer::from_slice(input)).map_err(DeserializeOneError::from_path_to_error)?,
             #[cfg(feature = "serde-xml-rs")]
-            Format::Xml => serde_xml_rs::from_str(input)?,
+            Format::Xml => {
+                let reader = crate::counting_reader::CountingReader::new(input);
+                let count = reader.count.clone();
+                serde_path_to_error::deserialize(&mut serde_xml_rs::Deserializer::new_from_reader(reader)).map_err(|error| DeserializeOneError::from_path_to_error(error).with_offset(count.get()))?
+            }
             #[cfg(feature = "quick-xml")]
-            Format::Xml => quick_xml::de::from_str(input)?,
+            Format::Xml => {
+                let reader = crate::counting_reader::CountingReader::new(input);
+                let count = reader.count.clone();
+                serde_path_to_error::deserialize(&mut quick_xml::de::Deserializer::from_reader(reader)).map_err(|error| DeserializeOneError::from_path_to_error(error).with_offset(count.get()))?
+            }
             #[cfg(feature = "toml")]
-            Format::Toml => toml::from_str(input)?,
+            Format::Toml => {
+                let string = std::str::from_utf8(input).map_err(DeserializeOneError::from)?;
+                let deserializer = toml::Deserializer::parse(string)?;
+                serde_path_to_error::deserialize(deserializer).map_err(DeserializeOneError::from_path_to_error)?
+            }
             #[cfg(feature = "csv")]
-            Format::Csv => {
-                // NOTE: The input must contain the columns
-                let mut reader = csv::Reader::from_reader(input.as_bytes());
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                // NOTE: The input must contain the columns (unless `has_headers` is false)
+                let mut reader = options
+                    .csv
+                    .unwrap_or_else(|| self.csv_options())
+                    .reader_builder()
+                    .from_reader(input);
+                let mut iter = reader.deserialize();
+                iter.next()
+                    .ok_or(crate::errors::item_not_found_error::ItemNotFoundError)??
+            }
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => {
+                let mut rest = input;
+                let output = ciborium::from_reader(&mut rest)?;
+                if !rest.is_empty() {
+                    let offset = input.len().saturating_sub(rest.len());
+                    Err(ciborium::de::Error::<std::io::Error>::Semantic(Some(offset), "trailing data after the value".to_string()))?
+                }
+                output
+            }
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => {
+                let mut deserializer = rmp_serde::Deserializer::new(input);
+                let output = serde_path_to_error::deserialize(&mut deserializer).map_err(DeserializeOneError::from_path_to_error)?;
+                if !deserializer.get_ref().is_empty() {
+                    Err(rmp_serde::decode::Error::Syntax("trailing data after the value".to_string()))?
+                }
+                output
+            }
+            #[cfg(feature = "bincode")]
+            Format::Bincode => crate::compact_codec::decode_bincode(
+                crate::binary_header::BinaryHeader::BINCODE
+                    .with_schema_version(options.schema_version)
+                    .strip(self, input)?,
+            )?,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => crate::compact_codec::decode_postcard(
+                crate::binary_header::BinaryHeader::POSTCARD
+                    .with_schema_version(options.schema_version)
+                    .strip(self, input)?,
+            )?,
+            #[cfg(feature = "ron")]
+            Format::Ron => {
+                let mut deserializer = ron::Deserializer::from_bytes(input)?;
+                let output = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| DeserializeOneError::from_path_to_error_with(error, |error| deserializer.span_error(error).into()))?;
+                deserializer
+                    .end()
+                    .map_err(|error| deserializer.span_error(error))?;
+                output
+            }
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => {
+                let string = std::str::from_utf8(input).map_err(DeserializeOneError::from)?;
+                serde_path_to_error::deserialize(crate::ini_tree::parse(string)?).map_err(DeserializeOneError::from_path_to_error)?
+            }
+            #[cfg(feature = "properties")]
+            Format::Properties => {
+                let string = std::str::from_utf8(input).map_err(DeserializeOneError::from)?;
+                serde_path_to_error::deserialize(crate::properties::parse(string)?).map_err(DeserializeOneError::from_path_to_error)?
+            }
+        })
+    }
+
+    /// Deserializes from a reader (wrap it in a [`BufReader`](std::io::BufReader) if it is unbuffered)
+    ///
+    /// The input is not kept, so the columns of the JSON errors count the bytes instead of the characters (see [`Format::deserialize_one_from_slice`]).
+    pub fn deserialize_one_from_reader<T: DeserializeOwned>(self, reader: impl Read) -> Result<T, DeserializeOneError> {
+        self.deserialize_one_from_reader_with(reader, &FormatOptions::default())
+    }
+
+    /// Deserializes from a reader with the options that apply to the input (the dialect of the CSV-like formats)
+    #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
+    pub fn deserialize_one_from_reader_with<T: DeserializeOwned>(self, mut reader: impl Read, options: &FormatOptions) -> Result<T, DeserializeOneError> {
+        Ok(match self {
+            #[cfg(feature = "serde_json")]
+            Format::Json => {
+                let mut deserializer = serde_json::Deserializer::from_reader(reader);
+                let output = serde_path_to_error::deserialize(&mut deserializer).map_err(DeserializeOneError::from_path_to_error)?;
+                deserializer.end()?;
+                output
+            }
+            #[cfg(feature = "serde-jsonlines")]
+            Format::Jsonl => {
+                let mut reader = serde_jsonlines::JsonLinesReader::new(std::io::BufReader::new(reader));
+                reader
+                    .read()
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::SerdeJsonlines)?
+                    .ok_or(crate::errors::item_not_found_error::ItemNotFoundError)?
+            }
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io)?;
+                self.deserialize_one_from_slice_with(&bytes, options)?
+            }
+            #[cfg(feature = "serde_yaml")]
+            Format::Yaml => serde_path_to_error::deserialize(serde_yaml::Deserializer::from_reader(reader)).map_err(DeserializeOneError::from_path_to_error)?,
+            #[cfg(feature = "serde-xml-rs")]
+            Format::Xml => {
+                let reader = crate::counting_reader::CountingReader::new(reader);
+                let count = reader.count.clone();
+                serde_path_to_error::deserialize(&mut serde_xml_rs::Deserializer::new_from_reader(reader)).map_err(|error| DeserializeOneError::from_path_to_error(error).with_offset(count.get()))?
+            }
+            #[cfg(feature = "quick-xml")]
+            Format::Xml => {
+                let reader = crate::counting_reader::CountingReader::new(std::io::BufReader::new(reader));
+                let count = reader.count.clone();
+                serde_path_to_error::deserialize(&mut quick_xml::de::Deserializer::from_reader(reader)).map_err(|error| DeserializeOneError::from_path_to_error(error).with_offset(count.get()))?
+            }
+            #[cfg(feature = "toml")]
+            Format::Toml => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io)?;
+                self.deserialize_one_from_slice_with(&bytes, options)?
+            }
+            #[cfg(feature = "csv")]
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                // NOTE: The input must contain the columns (unless `has_headers` is false)
+                let mut reader = options
+                    .csv
+                    .unwrap_or_else(|| self.csv_options())
+                    .reader_builder()
+                    .from_reader(reader);
                 let mut iter = reader.deserialize();
                 iter.next()
-                    .ok_or::<DeserializeOneError>(crate::errors::item_not_found_error::ItemNotFoundError.into())??
+                    .ok_or(crate::errors::item_not_found_error::ItemNotFoundError)??
+            }
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => {
+                // The decoder reads exactly the bytes of the value, so a byte that can still be read is trailing data
+                let mut reader = crate::counting_reader::CountingReader::new(reader);
+                let output = ciborium::from_reader(&mut reader)?;
+                if has_trailing_data(&mut reader)? {
+                    let offset = reader.count.get().saturating_sub(1);
+                    Err(ciborium::de::Error::<std::io::Error>::Semantic(Some(offset), "trailing data after the value".to_string()))?
+                }
+                output
+            }
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => {
+                let mut deserializer = rmp_serde::Deserializer::new(reader);
+                let output = serde_path_to_error::deserialize(&mut deserializer).map_err(DeserializeOneError::from_path_to_error)?;
+                if has_trailing_data(deserializer.get_mut())? {
+                    Err(rmp_serde::decode::Error::Syntax("trailing data after the value".to_string()))?
+                }
+                output
+            }
+            // The compact backends decode from slices, so the value is read into memory first
+            #[cfg(feature = "bincode")]
+            Format::Bincode => self.deserialize_one_from_slice_with(&crate::compact_codec::read_to_vec(reader)?, options)?,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => self.deserialize_one_from_slice_with(&crate::compact_codec::read_to_vec(reader)?, options)?,
+            #[cfg(feature = "ron")]
+            Format::Ron => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io)?;
+                self.deserialize_one_from_slice_with(&bytes, options)?
+            }
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io)?;
+                self.deserialize_one_from_slice_with(&bytes, options)?
+            }
+            #[cfg(feature = "properties")]
+            Format::Properties => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io)?;
+                self.deserialize_one_from_slice_with(&bytes, options)?
             }
         })
     }
 
+    pub fn deserialize_many_from_reader<T: DeserializeOwned + 'static>(self, reader: impl BufRead + 'static) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, DeserializeManyError> {
+        self.deserialize_many_from_reader_with(reader, &ManyOptions::default())
+    }
+
+    /// Deserializes the items one by one where the backend allows it (an invalid item is yielded as an error, and the iteration continues where the format can resynchronize)
+    ///
+    /// JSONC, JSON5, TOML, RON, INI and properties have no streaming parsers (an INI section may be extended by a later one), so this method and [`Format::deserialize_one_from_reader`] read their whole document into memory first.
+    ///
+    /// RON, JSONC and JSON5 are read eagerly: the whole top-level sequence is deserialized into a `Vec<T>` before the first item is returned, so the memory use grows with the input, and the first invalid item fails the call instead of being yielded.
     #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
-    pub fn deserialize_many_from_reader<T: DeserializeOwned + 'static>(self, mut reader: impl BufRead + 'static) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, DeserializeManyError> {
+    pub fn deserialize_many_from_reader_with<T: DeserializeOwned + 'static>(self, mut reader: impl BufRead + 'static, options: &ManyOptions) -> Result<Box<dyn Iterator<Item = Result<T, DeserializeOneError>>>, DeserializeManyError> {
         Ok(match self {
             #[cfg(feature = "serde_json")]
-            Format::Json => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
+            Format::Json => Box::new(crate::json_array_iter::JsonArrayIter::new(reader)),
             #[cfg(feature = "serde-jsonlines")]
             Format::Jsonl => {
                 let reader = serde_jsonlines::JsonLinesReader::new(reader);
                 let iter = reader
                     .read_all()
-                    .map(|x| x.map_err(DeserializeOneError::SerdeJsonlines));
+                    .map(|x| x.map_err(|error| crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::SerdeJsonlines(error).into()));
                 Box::new(iter)
             }
+            #[cfg(feature = "json5")]
+            Format::Jsonc | Format::Json5 => {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                let items = self.deserialize_one_from_slice::<Vec<T>>(&bytes)?;
+                Box::new(items.into_iter().map(Ok))
+            }
             #[cfg(feature = "serde_yaml")]
-            Format::Yaml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
-            #[cfg(feature = "serde-xml-rs")]
-            Format::Xml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
-                format: self,
-            })?,
+            Format::Yaml => Box::new(crate::yaml_documents_iter::YamlDocumentsIter::new(reader)),
+            // The many-item XML support is built on quick-xml, so it takes precedence over serde-xml-rs here
             #[cfg(feature = "quick-xml")]
+            Format::Xml => Box::new(crate::xml_elements_iter::XmlElementsIter::new(reader, options.xml_root_tag.clone(), options.xml_item_tag.clone())),
+            #[cfg(all(feature = "serde-xml-rs", not(feature = "quick-xml")))]
             Format::Xml => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
                 format: self,
             })?,
             #[cfg(feature = "toml")]
             Format::Toml => {
-                // crate::errors::unsupported_format_error::UnsupportedFormatError {
-                //     format: self,
-                // }.into()
-                // TODO: TOML doesn't support line-by-line deserialization, so using it with reader doesn't make sense
-                // TODO: Decide on how to handle such formats (most likely )
-                let mut s = String::new();
-                reader.read_to_string(&mut s)?;
-                let vec = self.deserialize_one::<Vec<T>>(&s)?;
-                Box::new(vec.into_iter().map(Ok))
+                // The items are deserialized one by one from the parsed table
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                let string = std::str::from_utf8(&bytes).map_err(|error| DeserializeOneError::from(error).with_input(&bytes))?;
+                let mut table = toml::from_str::<toml::Table>(string).map_err(|error| DeserializeOneError::from(error).with_input(&bytes))?;
+                let not_found = || crate::errors::toml_array_not_found_error::TomlArrayNotFoundError {
+                    key: options.toml_key.clone(),
+                };
+                let array = match table.remove(&options.toml_key).ok_or_else(not_found)? {
+                    toml::Value::Array(array) => array,
+                    _ => Err(not_found())?,
+                };
+                let iter = array
+                    .into_iter()
+                    .map(|value| serde_path_to_error::deserialize(value).map_err(DeserializeOneError::from_path_to_error));
+                Box::new(iter)
             }
             #[cfg(feature = "csv")]
-            Format::Csv => {
-                // NOTE: The input must contain the columns
-                let iter = csv::Reader::from_reader(reader)
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                // NOTE: The input must contain the columns (unless `has_headers` is false)
+                let csv_options = options.format.csv.unwrap_or_else(|| self.csv_options());
+                let iter = csv_options
+                    .reader_builder()
+                    .from_reader(reader)
                     .into_deserialize()
-                    .map(|result| result.map_err(DeserializeOneError::Csv));
+                    .map(|result| result.map_err(DeserializeOneError::from));
                 Box::new(iter)
             }
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => Box::new(crate::concatenated_items_iter::ConcatenatedItemsIter::new(reader, |reader| Ok(ciborium::from_reader(reader)?))),
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => Box::new(crate::concatenated_items_iter::ConcatenatedItemsIter::new(reader, |reader| serde_path_to_error::deserialize(&mut rmp_serde::Deserializer::new(reader)).map_err(DeserializeOneError::from_path_to_error))),
+            #[cfg(feature = "bincode")]
+            Format::Bincode => {
+                crate::binary_header::BinaryHeader::BINCODE
+                    .with_schema_version(options.format.schema_version)
+                    .read(self, &mut reader)?;
+                Box::new(crate::concatenated_items_iter::ConcatenatedItemsIter::new(reader, |reader| crate::compact_codec::decode_bincode(&crate::length_prefix::read_length_prefixed(reader)?)))
+            }
+            #[cfg(feature = "postcard")]
+            Format::Postcard => {
+                crate::binary_header::BinaryHeader::POSTCARD
+                    .with_schema_version(options.format.schema_version)
+                    .read(self, &mut reader)?;
+                Box::new(crate::concatenated_items_iter::ConcatenatedItemsIter::new(reader, |reader| crate::compact_codec::decode_postcard(&crate::length_prefix::read_length_prefixed(reader)?)))
+            }
+            #[cfg(feature = "ron")]
+            Format::Ron => {
+                // The untyped RON values lose the enum variants, so the items are not deserialized one by one
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                let items = self.deserialize_one_from_slice::<Vec<T>>(&bytes)?;
+                Box::new(items.into_iter().map(Ok))
+            }
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
+                format: self,
+            })?,
+            #[cfg(feature = "properties")]
+            Format::Properties => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
+                format: self,
+            })?,
         })
     }
 
@@ -276,6 +1038,10 @@ impl Format {
         match self {
             #[cfg(feature = "serde_json")]
             Format::Json => "json",
+            #[cfg(feature = "json5")]
+            Format::Jsonc => "jsonc",
+            #[cfg(feature = "json5")]
+            Format::Json5 => "json5",
             #[cfg(feature = "serde-jsonlines")]
             Format::Jsonl => "jsonl",
             #[cfg(feature = "serde_yaml")]
@@ -284,11 +1050,82 @@ impl Format {
             Format::Xml => "xml",
             #[cfg(feature = "toml")]
             Format::Toml => "toml",
+            #[cfg(feature = "csv")]
+            Format::Csv | Format::SemicolonCsv => "csv",
+            #[cfg(feature = "csv")]
+            Format::Tsv => "tsv",
+            #[cfg(feature = "csv")]
+            Format::Psv => "psv",
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => "cbor",
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => "msgpack",
+            #[cfg(feature = "bincode")]
+            Format::Bincode => "bincode",
+            #[cfg(feature = "postcard")]
+            Format::Postcard => "postcard",
+            #[cfg(feature = "ron")]
+            Format::Ron => "ron",
+            #[cfg(feature = "rust-ini")]
+            Format::Ini => "ini",
+            #[cfg(feature = "properties")]
+            Format::Properties => "properties",
             #[allow(unreachable_patterns)]
             _ => "txt",
         }
     }
 
+    /// Returns `true` for the formats that are not text (their output is not valid UTF-8, so the text-only methods like [`Format::serialize_one`] and [`Format::print_one`] return [`BinaryFormatError`](crate::errors::binary_format_error::BinaryFormatError))
+    pub fn is_binary(&self) -> bool {
+        match self {
+            #[cfg(feature = "ciborium")]
+            Format::Cbor => true,
+            #[cfg(feature = "rmp-serde")]
+            Format::MessagePack => true,
+            #[cfg(feature = "bincode")]
+            Format::Bincode => true,
+            #[cfg(feature = "postcard")]
+            Format::Postcard => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Returns the header that prefixes the output of the compact Rust-native formats with the schema version 0 (`None` for the other formats, see [`BinaryHeader::with_schema_version`](crate::binary_header::BinaryHeader::with_schema_version))
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    pub fn binary_header(&self) -> Option<crate::binary_header::BinaryHeader> {
+        match self {
+            #[cfg(feature = "bincode")]
+            Format::Bincode => Some(crate::binary_header::BinaryHeader::BINCODE),
+            #[cfg(feature = "postcard")]
+            Format::Postcard => Some(crate::binary_header::BinaryHeader::POSTCARD),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    fn ensure_text(self) -> Result<(), SerializeOneError> {
+        if self.is_binary() {
+            return Err(crate::errors::binary_format_error::BinaryFormatError {
+                format: self,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// The default dialect of the CSV-like formats
+    #[cfg(feature = "csv")]
+    pub fn csv_options(&self) -> crate::csv_options::CsvOptions {
+        use crate::csv_options::CsvOptions;
+        match self {
+            Format::Tsv => CsvOptions::with_delimiter(b'\t'),
+            Format::Psv => CsvOptions::with_delimiter(b'|'),
+            Format::SemicolonCsv => CsvOptions::with_delimiter(b';'),
+            _ => CsvOptions::default(),
+        }
+    }
+
     pub fn to_file_name(&self, stem: &str) -> String {
         format!("{stem}.{extension}", extension = self.to_file_extension())
     }
@@ -297,6 +1134,10 @@ impl Format {
         match extension.to_str() {
             #[cfg(feature = "serde_json")]
             Some("json") => Ok(Format::Json),
+            #[cfg(feature = "json5")]
+            Some("jsonc") => Ok(Format::Jsonc),
+            #[cfg(feature = "json5")]
+            Some("json5") => Ok(Format::Json5),
             #[cfg(feature = "serde-jsonlines")]
             Some("jsonl") => Ok(Format::Jsonl),
             #[cfg(feature = "serde_yaml")]
@@ -305,6 +1146,26 @@ impl Format {
             Some("xml") => Ok(Format::Xml),
             #[cfg(feature = "toml")]
             Some("toml") => Ok(Format::Toml),
+            #[cfg(feature = "csv")]
+            Some("csv") => Ok(Format::Csv),
+            #[cfg(feature = "csv")]
+            Some("tsv") => Ok(Format::Tsv),
+            #[cfg(feature = "csv")]
+            Some("psv") => Ok(Format::Psv),
+            #[cfg(feature = "ciborium")]
+            Some("cbor") => Ok(Format::Cbor),
+            #[cfg(feature = "rmp-serde")]
+            Some("msgpack") => Ok(Format::MessagePack),
+            #[cfg(feature = "bincode")]
+            Some("bincode") => Ok(Format::Bincode),
+            #[cfg(feature = "postcard")]
+            Some("postcard") => Ok(Format::Postcard),
+            #[cfg(feature = "ron")]
+            Some("ron") => Ok(Format::Ron),
+            #[cfg(feature = "rust-ini")]
+            Some("ini") | Some("cfg") => Ok(Format::Ini),
+            #[cfg(feature = "properties")]
+            Some("properties") => Ok(Format::Properties),
             #[allow(unreachable_patterns)]
             _ => Err(UnrecognizedExtensionError {
                 extension: extension.to_owned(),
@@ -312,12 +1173,48 @@ impl Format {
         }
     }
 
+    /// Returns [`Format::SemicolonCsv`] for [`Format::Csv`] if the first line of the input has semicolons and no commas, `None` if the format stays the same
+    ///
+    /// Both dialects use the `.csv` extension (see [`Format::to_file_extension`]), so the methods that select the format by the extension call it to read back the semicolon files.
+    #[allow(unreachable_patterns, unused_variables)]
+    pub fn resolve_csv_dialect(self, input: &[u8]) -> Option<Self> {
+        match self {
+            #[cfg(feature = "csv")]
+            Format::Csv => {
+                let first_line = input
+                    .split(|byte| *byte == b'\n')
+                    .next()
+                    .unwrap_or_default();
+                (first_line.contains(&b';') && !first_line.contains(&b',')).then_some(Format::SemicolonCsv)
+            }
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the content, best guess first
+    ///
+    /// Only the formats enabled by the features are considered. The input may be a prefix of the content (the heuristics don't need the whole document, but a valid complete document gets a higher score).
+    pub fn detect(input: &[u8]) -> Vec<FormatGuess> {
+        crate::detect::detect(input)
+    }
+
+    /// Guesses the format from the buffered content of the reader without consuming it (see [`Format::detect`])
+    pub fn detect_from_reader(reader: &mut impl BufRead) -> std::io::Result<Vec<FormatGuess>> {
+        let buffer = reader.fill_buf()?;
+        Ok(Self::detect(buffer))
+    }
+
+    /// Returns the format selected by the extension, skipping the compression extension if there is one (e.g. `events.jsonl.gz` is JSON Lines)
     pub fn try_from_path(path: impl AsRef<Path>) -> Result<Self, TryFromPathError> {
-        let extension = path
-            .as_ref()
+        let path = path.as_ref();
+        let inner_path = match Compression::from_path(path) {
+            Some(_) => Path::new(path.file_stem().unwrap_or_default()),
+            None => path,
+        };
+        let extension = inner_path
             .extension()
             .ok_or_else(|| PathHasNoExtensionError {
-                path: path.as_ref().into(),
+                path: path.into(),
             })?;
         let format = Self::try_from_file_extension(extension)?;
         Ok(format)
@@ -347,3 +1244,482 @@ impl TryFrom<&Path> for Format {
         Self::try_from_path(value)
     }
 }
+
+/// Returns `true` if a byte can still be read after the value (the byte is consumed)
+#[cfg(any(feature = "ciborium", feature = "rmp-serde"))]
+fn has_trailing_data(reader: &mut impl Read) -> Result<bool, crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind> {
+    let mut byte = [0u8];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(len) => return Ok(len != 0),
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "serde_json", feature = "csv", feature = "ciborium", feature = "rmp-serde", feature = "bincode", feature = "postcard", feature = "ron", feature = "json5", feature = "toml", feature = "rust-ini", feature = "properties"))]
+    use super::*;
+
+    #[cfg(any(feature = "csv", feature = "json5", feature = "toml"))]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Row {
+        name: String,
+        port: u16,
+    }
+
+    /// Fails to serialize after the first entry has been written
+    #[cfg(feature = "serde_json")]
+    struct FailsMidway;
+
+    #[cfg(feature = "serde_json")]
+    impl Serialize for FailsMidway {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::{Error, SerializeMap};
+            let mut map = serializer.serialize_map(None)?;
+            map.serialize_entry("name", "a")?;
+            Err(S::Error::custom("the port is not set"))
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_keep_target_when_serialization_fails_midway() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "{\"name\":\"old\"}").unwrap();
+        let error = Format::Json.save_one(&path, &FailsMidway).unwrap_err();
+        assert_eq!(error.operation, crate::operation::Operation::Serialize);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"name\":\"old\"}");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        let path = dir.path().join("missing").join("config.json");
+        let error = Format::Json.save_one(&path, &FailsMidway).unwrap_err();
+        assert_eq!(error.operation, crate::operation::Operation::Save);
+        assert!(matches!(error.source, WriteOneError::Io(ref error) if error.kind() == std::io::ErrorKind::NotFound), "{error:?}");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[derive(Deserialize, Debug)]
+    struct Server {
+        port: u16,
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_deserialize_one_from_slice() {
+        let servers = Format::Json
+            .deserialize_one_from_slice::<Vec<Server>>(b"[{\"port\": 80}, {\"port\": 443}]")
+            .unwrap();
+        assert_eq!(servers.iter().map(|server| server.port).collect::<Vec<_>>(), vec![80, 443]);
+        let error = Format::Json
+            .deserialize_one_from_slice::<Vec<Server>>(b"[\n  {\"port\": 80},\n  {\"port\": \"x\"}\n]")
+            .unwrap_err();
+        assert_eq!(error.location.and_then(|location| location.line), Some(3));
+        assert_eq!(error.location.and_then(|location| location.column), Some(14));
+        assert_eq!(error.field_path.as_deref(), Some("[1].port"));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_deserialize_one_from_reader() {
+        let input = b"[{\"port\": 80}, {\"port\": 443}]";
+        let servers = Format::Json
+            .deserialize_one_from_reader::<Vec<Server>>(std::io::BufReader::new(input.as_slice()))
+            .unwrap();
+        assert_eq!(servers.iter().map(|server| server.port).collect::<Vec<_>>(), vec![80, 443]);
+        let input = b"[\n  {\"port\": 80},\n  {\"port\": \"x\"}\n]";
+        let error = Format::Json
+            .deserialize_one_from_reader::<Vec<Server>>(std::io::BufReader::new(input.as_slice()))
+            .unwrap_err();
+        assert_eq!(error.location.and_then(|location| location.line), Some(3));
+        assert_eq!(error.location.and_then(|location| location.column), Some(14));
+        assert_eq!(error.field_path.as_deref(), Some("[1].port"));
+    }
+
+    /// Fails to serialize if the flag is set, writes the keys in the reverse order otherwise
+    #[cfg(feature = "serde_json")]
+    struct MaybeFails(bool);
+
+    #[cfg(feature = "serde_json")]
+    impl Serialize for MaybeFails {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::{Error, SerializeMap};
+            if self.0 {
+                return Err(S::Error::custom("the item is invalid"));
+            }
+            let mut map = serializer.serialize_map(None)?;
+            map.serialize_entry("b", &1)?;
+            map.serialize_entry("a", &2)?;
+            map.end()
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_stream_canonical_items() {
+        let options = ManyOptions {
+            format: FormatOptions::canonical(),
+            ..ManyOptions::default()
+        };
+        let mut output = Vec::new();
+        Format::Json
+            .serialize_many_to_writer_with(&[MaybeFails(false), MaybeFails(false)], &mut output, &options)
+            .unwrap();
+        assert_eq!(output, br#"[{"a":2,"b":1},{"a":2,"b":1}]"#);
+        // The items before the invalid one are already written, so they were not collected first
+        let mut output = Vec::new();
+        let error = Format::Json
+            .serialize_many_to_writer_with(&[MaybeFails(false), MaybeFails(true), MaybeFails(false)], &mut output, &options)
+            .unwrap_err();
+        assert!(matches!(error, SerializeManyError::Canonicalize(_)), "{error:?}");
+        assert_eq!(output, br#"[{"a":2,"b":1}"#);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_apply_csv_options_to_one_item() {
+        let options = FormatOptions {
+            csv: Some(crate::csv_options::CsvOptions::with_delimiter(b'|')),
+            ..FormatOptions::default()
+        };
+        let row = Row {
+            name: "a".to_string(),
+            port: 80,
+        };
+        let output = Format::Csv.serialize_one_with(&row, &options).unwrap();
+        assert_eq!(output, "name|port\na|80\n");
+        assert_eq!(
+            Format::Csv
+                .deserialize_one_from_slice_with::<Row>(output.as_bytes(), &options)
+                .unwrap(),
+            row
+        );
+        assert_eq!(
+            Format::Csv
+                .deserialize_one_from_reader_with::<Row>(output.as_bytes(), &options)
+                .unwrap(),
+            row
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_load_semicolon_csv_saved_with_csv_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let row = Row {
+            name: "a,b".to_string(),
+            port: 80,
+        };
+        let path = dir.path().join(Format::SemicolonCsv.to_file_name("rows"));
+        Format::SemicolonCsv.save_one(&path, &row).unwrap();
+        assert_eq!(Format::load_one_as::<Row>(&path).unwrap(), row);
+        assert_eq!(Format::load_one_auto::<Row>(&path).unwrap(), row);
+        let rows = Format::load_many_as::<Row>(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![row]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_keep_comma_csv_with_semicolons_in_values() {
+        assert_eq!(Format::Csv.resolve_csv_dialect(b"name,port\na;b,80\n"), None);
+        assert_eq!(Format::Csv.resolve_csv_dialect(b"name;port\na;80\n"), Some(Format::SemicolonCsv));
+        assert_eq!(Format::Tsv.resolve_csv_dialect(b"name;port\n"), None);
+    }
+
+    #[cfg(feature = "ciborium")]
+    #[test]
+    fn must_reject_trailing_data_after_cbor_value() {
+        let mut input = Format::Cbor.serialize_one_to_vec(&[1u8, 2]).unwrap();
+        assert_eq!(
+            Format::Cbor
+                .deserialize_one_from_reader::<Vec<u8>>(input.as_slice())
+                .unwrap(),
+            vec![1, 2]
+        );
+        input.push(0);
+        let error = Format::Cbor
+            .deserialize_one_from_slice::<Vec<u8>>(&input)
+            .unwrap_err();
+        assert!(error.to_string().contains("trailing data"), "{error}");
+        let error = Format::Cbor
+            .deserialize_one_from_reader::<Vec<u8>>(input.as_slice())
+            .unwrap_err();
+        assert!(error.to_string().contains("trailing data"), "{error}");
+    }
+
+    #[cfg(feature = "rmp-serde")]
+    #[test]
+    fn must_reject_trailing_data_after_message_pack_value() {
+        let mut input = Format::MessagePack.serialize_one_to_vec(&[1u8, 2]).unwrap();
+        assert_eq!(
+            Format::MessagePack
+                .deserialize_one_from_reader::<Vec<u8>>(input.as_slice())
+                .unwrap(),
+            vec![1, 2]
+        );
+        input.push(0);
+        let error = Format::MessagePack
+            .deserialize_one_from_reader::<Vec<u8>>(input.as_slice())
+            .unwrap_err();
+        assert!(error.to_string().contains("trailing data"), "{error}");
+    }
+
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct CacheV1 {
+        id: u32,
+        name: String,
+    }
+
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct CacheV2 {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+    }
+
+    /// Returns the schema version of the input if the error is a schema mismatch
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    fn found_schema_version(error: &DeserializeOneError) -> Option<u32> {
+        match *error.source {
+            crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::IncompatibleHeader(crate::errors::incompatible_header_error::IncompatibleHeaderError {
+                found: Some(found),
+                ..
+            }) => Some(found.schema_version),
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    fn assert_schema_version_checked(format: Format) {
+        let v1 = FormatOptions {
+            schema_version: 1,
+            ..FormatOptions::default()
+        };
+        let v2 = FormatOptions {
+            schema_version: 2,
+            ..FormatOptions::default()
+        };
+        let value = CacheV1 {
+            id: 1,
+            name: "a".to_string(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format.to_file_name("cache"));
+        let save_options = SaveOneOptions {
+            format: v1,
+            ..SaveOneOptions::default()
+        };
+        format.save_one_with(&path, &value, &save_options).unwrap();
+        assert_eq!(format.load_one_with::<CacheV1>(&path, &v1).unwrap(), value);
+        let error = format.load_one_with::<CacheV2>(&path, &v2).unwrap_err();
+        let crate::errors::load_one_error_kind::LoadOneErrorKind::DeserializeOne(source) = &error.source else {
+            panic!("{format}: {error:?}");
+        };
+        assert_eq!(found_schema_version(source), Some(1), "{format}: {error:?}");
+        assert_eq!(source.source.to_string(), "the input was written with schema version 1, but schema version 2 was expected");
+        let error = format.load_one::<CacheV1>(&path).unwrap_err();
+        assert_eq!(error.operation, crate::operation::Operation::Deserialize, "{format}: {error:?}");
+
+        let mut bytes = Vec::new();
+        let v1_many = ManyOptions {
+            format: v1,
+            ..ManyOptions::default()
+        };
+        format
+            .serialize_many_to_writer_with([&value, &value], &mut bytes, &v1_many)
+            .unwrap();
+        let items = format
+            .deserialize_many_from_reader_with::<CacheV1>(std::io::Cursor::new(bytes.clone()), &v1_many)
+            .unwrap();
+        assert_eq!(items.map(Result::unwrap).count(), 2);
+        let v2_many = ManyOptions {
+            format: v2,
+            ..ManyOptions::default()
+        };
+        let Err(DeserializeManyError::DeserializeOneError(error)) = format.deserialize_many_from_reader_with::<CacheV2>(std::io::Cursor::new(bytes), &v2_many) else {
+            panic!("{format}: the mismatched schema version was accepted");
+        };
+        assert_eq!(found_schema_version(&error), Some(1), "{format}: {error:?}");
+    }
+
+    #[cfg(any(feature = "bincode", feature = "postcard"))]
+    #[test]
+    fn must_reject_mismatched_schema_version() {
+        #[cfg(feature = "bincode")]
+        assert_schema_version_checked(Format::Bincode);
+        #[cfg(feature = "postcard")]
+        assert_schema_version_checked(Format::Postcard);
+    }
+
+    #[cfg(any(feature = "rust-ini", feature = "properties"))]
+    #[test]
+    fn must_reject_dotted_keys_in_string_tree_formats() {
+        let map = std::collections::BTreeMap::from([("a", "2"), ("a.b", "1")]);
+        let assert_rejected = |format: Format| {
+            let error = format.serialize_one(&map).unwrap_err();
+            let SerializeOneError::StringTree(error) = error else {
+                panic!("{format}: {error:?}");
+            };
+            assert_eq!(error.message, "the key \"a.b\" contains a dot, which would be read back as a nested key", "{format}");
+            assert!(
+                format
+                    .serialize_one(&std::collections::BTreeMap::from([("a", "2")]))
+                    .is_ok(),
+                "{format}"
+            );
+        };
+        #[cfg(feature = "rust-ini")]
+        assert_rejected(Format::Ini);
+        #[cfg(feature = "properties")]
+        assert_rejected(Format::Properties);
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn must_read_ron_items_eagerly() {
+        let items = vec![Some(1u8), None];
+        let mut output = Vec::new();
+        Format::Ron
+            .serialize_many_to_writer(&items, &mut output)
+            .unwrap();
+        let output = Format::Ron
+            .deserialize_many_from_reader::<Option<u8>>(std::io::Cursor::new(output))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(output, items);
+        assert!(
+            Format::Ron
+                .deserialize_many_from_reader::<u8>(std::io::Cursor::new(b"[1, \"a\", 3]".to_vec()))
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn must_read_lenient_jsonc() {
+        let row = Row {
+            name: "a".to_string(),
+            port: 1,
+        };
+        assert_eq!(
+            Format::Jsonc
+                .deserialize_one_from_slice::<Row>(b"{name:'a', port:1}")
+                .unwrap(),
+            row
+        );
+        let input = b"// settings\n{\n  \"name\": \"a\", /* the default port */\n  \"port\": 1,\n}\n";
+        assert_eq!(
+            Format::Jsonc
+                .deserialize_one_from_slice::<Row>(input)
+                .unwrap(),
+            row
+        );
+        assert_eq!(
+            Format::Jsonc
+                .deserialize_one_from_reader::<Row>(input.as_slice())
+                .unwrap(),
+            row
+        );
+        assert_eq!(Format::Jsonc.serialize_one(&row).unwrap(), Format::Json.serialize_one(&row).unwrap());
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn must_read_many_jsonc_items() {
+        let input = b"[\n  // first\n  {name: 'a', port: 1},\n  {name: 'b', port: 2},\n]".to_vec();
+        let rows = Format::Jsonc
+            .deserialize_many_from_reader::<Row>(std::io::Cursor::new(input))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.iter().map(|row| row.port).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn must_locate_jsonc_error() {
+        let error = Format::Jsonc
+            .deserialize_one_from_slice::<Row>(b"{\n  // comment\n  name: 'a',\n  port: 'x',\n}")
+            .unwrap_err();
+        assert_eq!(error.location.and_then(|location| location.line), Some(4));
+        assert!(error.to_string().starts_with("4:"), "{error}");
+    }
+
+    #[cfg(any(feature = "json5", feature = "toml", feature = "rust-ini", feature = "properties"))]
+    #[test]
+    fn must_report_invalid_utf8_in_every_text_format() {
+        use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+        let assert_utf8_error = |format: Format, input: &[u8]| {
+            let error = format
+                .deserialize_one_from_slice::<serde::de::IgnoredAny>(input)
+                .unwrap_err();
+            assert!(matches!(*error.source, DeserializeOneErrorKind::Utf8(_)), "{format}: {error}");
+            assert_eq!(error.location.and_then(|location| location.line), Some(2), "{format}: {error}");
+        };
+        #[cfg(feature = "json5")]
+        assert_utf8_error(Format::Json5, b"{\n  a: '\xff'\n}");
+        #[cfg(feature = "toml")]
+        assert_utf8_error(Format::Toml, b"a = 1\nb = '\xff'\n");
+        #[cfg(feature = "rust-ini")]
+        assert_utf8_error(Format::Ini, b"a = 1\nb = \xff\n");
+        #[cfg(feature = "properties")]
+        assert_utf8_error(Format::Properties, b"a = 1\nb = \xff\n");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn must_read_many_toml_items_under_key() {
+        let options = ManyOptions {
+            toml_key: "servers".to_string(),
+            ..ManyOptions::default()
+        };
+        let rows = vec![
+            Row {
+                name: "a".to_string(),
+                port: 1,
+            },
+            Row {
+                name: "b".to_string(),
+                port: 2,
+            },
+        ];
+        let mut output = Vec::new();
+        Format::Toml
+            .serialize_many_to_writer_with(&rows, &mut output, &options)
+            .unwrap();
+        assert!(String::from_utf8_lossy(&output).starts_with("[[servers]]\n"));
+        let output = Format::Toml
+            .deserialize_many_from_reader_with::<Row>(std::io::Cursor::new(output), &options)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(output, rows);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn must_yield_invalid_toml_item_and_continue() {
+        let input = b"[[item]]\nname = \"a\"\nport = \"x\"\n\n[[item]]\nname = \"b\"\nport = 2\n".to_vec();
+        let items = Format::Toml
+            .deserialize_many_from_reader::<Row>(std::io::Cursor::new(input))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].as_ref().unwrap_err().to_string().contains("port"));
+        assert_eq!(items[1].as_ref().unwrap().port, 2);
+        let error = Format::Toml
+            .deserialize_many_from_reader::<Row>(std::io::Cursor::new(b"[[other]]\nport = 1\n".to_vec()))
+            .err()
+            .unwrap();
+        assert!(matches!(error, DeserializeManyError::TomlArrayNotFound(_)), "{error:?}");
+    }
+}
@@ -0,0 +1,187 @@
+use crate::compression::{Compression, decompress_for_path};
+use crate::errors::deserialize_many_error::DeserializeManyError;
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::load_many_error::LoadManyError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::save_one_error::SaveOneError;
+use crate::format::Format;
+use crate::format_options::FormatOptions;
+use crate::many_options::ManyOptions;
+use crate::save_mode::SaveMode;
+use crate::save_one_options::SaveOneOptions;
+use crate::spawn_blocking::spawn_blocking;
+use crate::write_atomically::write_atomically_async;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io::Cursor;
+use std::path::Path;
+use tokio::io::{AsyncBufRead, BufReader};
+
+/// Async versions of the file operations (the files are read and written on tokio without blocking the runtime)
+impl Format {
+    /// Saves the value atomically (see [`SaveMode::Atomic`])
+    pub async fn save_one_async<T: Serialize>(self, path: impl AsRef<Path>, value: &T) -> Result<(), SaveOneError> {
+        self.save_one_async_with(path, value, &SaveOneOptions::default())
+            .await
+    }
+
+    /// The value is serialized in memory, then written to the file
+    pub async fn save_one_async_with<T: Serialize>(self, path: impl AsRef<Path>, value: &T, options: &SaveOneOptions) -> Result<(), SaveOneError> {
+        let path = path.as_ref();
+        let mut buffer = Vec::new();
+        self.write_one_maybe_compressed(&mut buffer, value, options, Compression::from_path(path))
+            .map_err(|source| SaveOneError::new(path, self, source))?;
+        let result = match options.mode {
+            SaveMode::Atomic => write_atomically_async(path, buffer).await,
+            SaveMode::Truncate => tokio::fs::write(path, &buffer).await,
+        };
+        result.map_err(|source| SaveOneError::new(path, self, source))
+    }
+
+    pub async fn load_one_async<T: DeserializeOwned + Send + 'static>(self, path: impl AsRef<Path>) -> Result<T, LoadOneError> {
+        self.load_one_async_with(path, &FormatOptions::default())
+            .await
+    }
+
+    /// The file is read without blocking, then decompressed and deserialized on the blocking thread pool
+    pub async fn load_one_async_with<T: DeserializeOwned + Send + 'static>(self, path: impl AsRef<Path>, options: &FormatOptions) -> Result<T, LoadOneError> {
+        let path = path.as_ref();
+        let options = *options;
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|source| LoadOneError::new(path, self, source))?;
+        let path_buf = path.to_path_buf();
+        spawn_blocking(move || {
+            let bytes = decompress_for_path(&path_buf, bytes).map_err(|source| LoadOneError::new(&path_buf, self, source))?;
+            self.deserialize_one_from_slice_with(&bytes, &options)
+                .map_err(|source| LoadOneError::from_input(&path_buf, self, source, &bytes))
+        })
+        .await
+        .map_err(|source| LoadOneError::new(path, self, source))?
+    }
+
+    pub async fn load_many_async<T: DeserializeOwned + Send + 'static>(self, path: impl AsRef<Path>) -> Result<BoxStream<'static, Result<T, DeserializeOneError>>, LoadManyError> {
+        self.load_many_async_with(path, &ManyOptions::default())
+            .await
+    }
+
+    /// Compressed files are decompressed in memory on the blocking thread pool (the async decoders are not supported yet)
+    pub async fn load_many_async_with<T: DeserializeOwned + Send + 'static>(self, path: impl AsRef<Path>, options: &ManyOptions) -> Result<BoxStream<'static, Result<T, DeserializeOneError>>, LoadManyError> {
+        let path = path.as_ref();
+        let result = match Compression::from_path(path) {
+            Some(_) => {
+                let bytes = tokio::fs::read(path)
+                    .await
+                    .map_err(|source| LoadManyError::new(path, self, source))?;
+                let path_buf = path.to_path_buf();
+                let bytes = spawn_blocking(move || decompress_for_path(&path_buf, bytes))
+                    .await
+                    .and_then(|result| result)
+                    .map_err(|source| LoadManyError::new(path, self, source))?;
+                self.deserialize_many_from_async_reader_with(Cursor::new(bytes), options)
+            }
+            None => {
+                let file = tokio::fs::File::open(path)
+                    .await
+                    .map_err(|source| LoadManyError::new(path, self, source))?;
+                self.deserialize_many_from_async_reader_with(BufReader::new(file), options)
+            }
+        };
+        result.map_err(|source| LoadManyError::new(path, self, source))
+    }
+
+    pub fn deserialize_many_from_async_reader<T: DeserializeOwned + Send + 'static>(self, reader: impl AsyncBufRead + Unpin + Send + 'static) -> Result<BoxStream<'static, Result<T, DeserializeOneError>>, DeserializeManyError> {
+        self.deserialize_many_from_async_reader_with(reader, &ManyOptions::default())
+    }
+
+    /// Only the line-based formats (JSONL and CSV) are supported
+    #[allow(unreachable_patterns, unused_variables, unreachable_code)]
+    pub fn deserialize_many_from_async_reader_with<T: DeserializeOwned + Send + 'static>(self, reader: impl AsyncBufRead + Unpin + Send + 'static, options: &ManyOptions) -> Result<BoxStream<'static, Result<T, DeserializeOneError>>, DeserializeManyError> {
+        Ok(match self {
+            #[cfg(feature = "serde-jsonlines")]
+            Format::Jsonl => serde_jsonlines::AsyncJsonLinesReader::new(reader)
+                .read_all()
+                .map(|x| x.map_err(|error| crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::SerdeJsonlines(error).into()))
+                .boxed(),
+            #[cfg(feature = "csv")]
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                let csv_options = options.format.csv.unwrap_or_else(|| self.csv_options());
+                crate::csv_records_stream::csv_records_stream(reader, csv_options).boxed()
+            }
+            _ => Err(crate::errors::unsupported_format_error::UnsupportedFormatError {
+                format: self,
+            })?,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod tests {
+    use super::*;
+    use strum::VariantArray;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn must_save_and_load_compressed_file_on_blocking_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let value = vec!["a".to_string(), "b".to_string()];
+        for compression in Compression::VARIANTS {
+            let path = dir
+                .path()
+                .join(format!("config.json.{}", compression.to_file_extension()));
+            let output: Vec<String> = block_on(async {
+                Format::Json.save_one_async(&path, &value).await.unwrap();
+                Format::Json.load_one_async(&path).await.unwrap()
+            });
+            assert_eq!(output, value, "{compression}");
+            assert_eq!(Format::Json.load_one::<Vec<String>>(&path).unwrap(), value, "{compression}");
+        }
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), Compression::VARIANTS.len());
+    }
+
+    #[cfg(feature = "serde-jsonlines")]
+    #[test]
+    fn must_load_many_from_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for compression in Compression::VARIANTS {
+            let path = dir
+                .path()
+                .join(format!("events.jsonl.{}", compression.to_file_extension()));
+            let items: Vec<Vec<u8>> = block_on(async {
+                Format::Jsonl
+                    .save_one_async(&path, &vec![1u8, 2])
+                    .await
+                    .unwrap();
+                let stream = Format::Jsonl.load_many_async(&path).await.unwrap();
+                stream.map(Result::unwrap).collect().await
+            });
+            assert_eq!(items, vec![vec![1, 2]], "{compression}");
+        }
+    }
+
+    #[test]
+    fn must_reject_level_out_of_range() {
+        let dir = tempfile::tempdir().unwrap();
+        for compression in Compression::VARIANTS {
+            let path = dir
+                .path()
+                .join(format!("config.json.{}", compression.to_file_extension()));
+            let options = SaveOneOptions {
+                compression_level: Some(50),
+                ..SaveOneOptions::default()
+            };
+            let error = block_on(Format::Json.save_one_async_with(&path, &1, &options)).unwrap_err();
+            assert!(matches!(error.source, crate::errors::write_one_error::WriteOneError::Io(ref error) if error.kind() == std::io::ErrorKind::InvalidInput), "{compression}: {error}");
+            assert!(!path.exists());
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::format::Format;
+
+/// A candidate format returned by [`Format::detect`](crate::format::Format::detect)
+///
+/// `score` is in `0..=100`: 100 means the input is valid in this format, lower scores mean that only the surface looks right.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct FormatGuess {
+    pub format: Format,
+    pub score: u8,
+}
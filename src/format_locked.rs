@@ -0,0 +1,49 @@
+use crate::errors::load_one_error::LoadOneError;
+use crate::errors::load_one_error_kind::LoadOneErrorKind;
+use crate::errors::load_one_locked_error::LoadOneLockedError;
+use crate::errors::update_one_error::UpdateOneError;
+use crate::file_lock::FileLock;
+use crate::format::Format;
+use crate::lock_options::LockOptions;
+use crate::update_one_options::UpdateOneOptions;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// The operations that coordinate the processes sharing a file via an advisory lock (see [`FileLock`])
+impl Format {
+    pub fn update_one<T: Serialize + DeserializeOwned + Default, R>(self, path: impl AsRef<Path>, update: impl FnOnce(&mut T) -> R) -> Result<R, UpdateOneError> {
+        self.update_one_with(path, &UpdateOneOptions::default(), update)
+    }
+
+    /// Loads the value (or takes the default value if the file doesn't exist), applies `update`, and saves the value, holding the exclusive lock for the whole time
+    ///
+    /// The value is saved even if `update` doesn't change it. Returns the output of `update`.
+    pub fn update_one_with<T: Serialize + DeserializeOwned + Default, R>(self, path: impl AsRef<Path>, options: &UpdateOneOptions, update: impl FnOnce(&mut T) -> R) -> Result<R, UpdateOneError> {
+        let path = path.as_ref();
+        let _lock = FileLock::exclusive(path, &options.lock)?;
+        let mut value = match self.load_one(path) {
+            Ok(value) => value,
+            Err(LoadOneError {
+                source: LoadOneErrorKind::Io(error),
+                ..
+            }) if error.kind() == ErrorKind::NotFound => T::default(),
+            Err(error) => return Err(error.into()),
+        };
+        let output = update(&mut value);
+        self.save_one_with(path, &value, &options.save)?;
+        Ok(output)
+    }
+
+    pub fn load_one_locked<T: DeserializeOwned>(self, path: impl AsRef<Path>) -> Result<T, LoadOneLockedError> {
+        self.load_one_locked_with(path, &LockOptions::default())
+    }
+
+    /// Loads the value holding the shared lock, so that the value is not loaded in the middle of [`Format::update_one`] (e.g. between the updates of two files that are locked together)
+    pub fn load_one_locked_with<T: DeserializeOwned>(self, path: impl AsRef<Path>, options: &LockOptions) -> Result<T, LoadOneLockedError> {
+        let path = path.as_ref();
+        let _lock = FileLock::shared(path, options)?;
+        Ok(self.load_one(path)?)
+    }
+}
@@ -0,0 +1,200 @@
+use crate::layout::Layout;
+use serde::{Deserialize, Serialize};
+
+/// Options for the output style (e.g. [`Format::serialize_one_with`](crate::format::Format::serialize_one_with))
+///
+/// TOML, YAML, JSON Lines and CSV have a fixed layout, so they ignore `layout` and the indentation (TOML only supports `toml_pretty_arrays`).
+/// The dialect of the CSV-like formats (`csv`) and the `schema_version` apply to the input too (e.g. [`Format::deserialize_one_from_slice_with`](crate::format::Format::deserialize_one_from_slice_with)).
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct FormatOptions {
+    pub layout: Layout,
+    pub indent_width: usize,
+    pub indent_char: char,
+    /// Append a newline if the output doesn't end with one
+    pub trailing_newline: bool,
+    /// Write each array element on its own line
+    pub toml_pretty_arrays: bool,
+    /// Write the names of the structs (e.g. `Config(port: 80)` instead of `(port: 80)`)
+    pub ron_struct_names: bool,
+    /// Write the arrays on a single line in the pretty layout
+    pub ron_compact_arrays: bool,
+    /// Produce byte-stable output (e.g. for content hashes): sort the map keys recursively, write `-0.0` as `0.0`, end the lines with `\n`, and follow RFC 8785 (JCS) for JSON and JSON Lines (which implies the compact layout)
+    ///
+    /// The many items are canonicalized one by one as they are written, so the memory use doesn't grow with their number (except for the formats that render the whole document in memory, like TOML and RON).
+    pub canonical: bool,
+    /// The version of the layout of the serialized type, written into the header of the compact Rust-native formats and checked on load (increment it on incompatible changes of the type, see [`Format::binary_header`](crate::format::Format::binary_header))
+    pub schema_version: u32,
+    /// Overrides the dialect of the CSV-like formats (`None` means [`Format::csv_options`](crate::format::Format::csv_options))
+    #[cfg(feature = "csv")]
+    pub csv: Option<crate::csv_options::CsvOptions>,
+}
+
+impl FormatOptions {
+    pub fn compact() -> Self {
+        Self {
+            layout: Layout::Compact,
+            ..Self::default()
+        }
+    }
+
+    pub fn pretty() -> Self {
+        Self {
+            layout: Layout::Pretty,
+            ..Self::default()
+        }
+    }
+
+    pub fn canonical() -> Self {
+        Self {
+            canonical: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn indent(&self) -> String {
+        self.indent_char.to_string().repeat(self.indent_width)
+    }
+
+    /// Returns `None` for the compact layout (RON is pretty by default, because it is mostly used for hand-edited files)
+    #[cfg(feature = "ron")]
+    pub fn ron_pretty_config(&self) -> Option<ron::ser::PrettyConfig> {
+        self.layout.is_pretty(true).then(|| {
+            ron::ser::PrettyConfig::new()
+                .indentor(self.indent())
+                .struct_names(self.ron_struct_names)
+                .compact_arrays(self.ron_compact_arrays)
+        })
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            layout: Layout::default(),
+            indent_width: 2,
+            indent_char: ' ',
+            trailing_newline: false,
+            toml_pretty_arrays: false,
+            ron_struct_names: false,
+            ron_compact_arrays: false,
+            canonical: false,
+            schema_version: 0,
+            #[cfg(feature = "csv")]
+            csv: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron", feature = "toml"))]
+    use super::*;
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron", feature = "toml"))]
+    use crate::format::Format;
+
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron", feature = "toml"))]
+    #[derive(Serialize)]
+    struct Config {
+        name: &'static str,
+        ports: Vec<u16>,
+    }
+
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron", feature = "toml"))]
+    fn serialize(format: Format, options: FormatOptions) -> String {
+        let config = Config {
+            name: "a",
+            ports: vec![80, 443],
+        };
+        format.serialize_one_with(&config, &options).unwrap()
+    }
+
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron"))]
+    fn tabs() -> FormatOptions {
+        FormatOptions {
+            indent_width: 1,
+            indent_char: '\t',
+            ..FormatOptions::pretty()
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_lay_out_json() {
+        let pretty = "{\n  \"name\": \"a\",\n  \"ports\": [\n    80,\n    443\n  ]\n}";
+        assert_eq!(serialize(Format::Json, FormatOptions::default()), pretty);
+        assert_eq!(serialize(Format::Json, FormatOptions::pretty()), pretty);
+        assert_eq!(serialize(Format::Json, FormatOptions::compact()), "{\"name\":\"a\",\"ports\":[80,443]}");
+        assert_eq!(
+            serialize(
+                Format::Json,
+                FormatOptions {
+                    indent_width: 4,
+                    ..FormatOptions::pretty()
+                }
+            ),
+            "{\n    \"name\": \"a\",\n    \"ports\": [\n        80,\n        443\n    ]\n}"
+        );
+        assert_eq!(serialize(Format::Json, tabs()), "{\n\t\"name\": \"a\",\n\t\"ports\": [\n\t\t80,\n\t\t443\n\t]\n}");
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    #[test]
+    fn must_keep_fixed_yaml_layout() {
+        let expected = "name: a\nports:\n- 80\n- 443\n";
+        assert_eq!(serialize(Format::Yaml, FormatOptions::default()), expected);
+        assert_eq!(serialize(Format::Yaml, FormatOptions::pretty()), expected);
+        assert_eq!(serialize(Format::Yaml, FormatOptions::compact()), expected);
+        assert_eq!(serialize(Format::Yaml, tabs()), expected);
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn must_lay_out_ron() {
+        let pretty = "(\n  name: \"a\",\n  ports: [\n    80,\n    443,\n  ],\n)";
+        assert_eq!(serialize(Format::Ron, FormatOptions::default()), pretty);
+        assert_eq!(serialize(Format::Ron, FormatOptions::pretty()), pretty);
+        assert_eq!(serialize(Format::Ron, FormatOptions::compact()), "(name:\"a\",ports:[80,443])");
+        assert_eq!(serialize(Format::Ron, tabs()), "(\n\tname: \"a\",\n\tports: [\n\t\t80,\n\t\t443,\n\t],\n)");
+        let options = FormatOptions {
+            ron_struct_names: true,
+            ron_compact_arrays: true,
+            ..FormatOptions::pretty()
+        };
+        assert_eq!(serialize(Format::Ron, options), "Config(\n  name: \"a\",\n  ports: [80, 443],\n)");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn must_lay_out_toml() {
+        let expected = "name = \"a\"\nports = [80, 443]\n";
+        assert_eq!(serialize(Format::Toml, FormatOptions::default()), expected);
+        assert_eq!(serialize(Format::Toml, FormatOptions::pretty()), expected);
+        assert_eq!(serialize(Format::Toml, FormatOptions::compact()), expected);
+        let options = FormatOptions {
+            toml_pretty_arrays: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(serialize(Format::Toml, options), "name = \"a\"\nports = [\n    80,\n    443,\n]\n");
+    }
+
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml", feature = "ron", feature = "toml"))]
+    #[test]
+    fn must_append_trailing_newline_once() {
+        let options = FormatOptions {
+            trailing_newline: true,
+            ..FormatOptions::compact()
+        };
+        let assert_one_newline = |format: Format| {
+            let output = serialize(format, options);
+            assert!(output.ends_with('\n') && !output.ends_with("\n\n"), "{format}: {output:?}");
+        };
+        #[cfg(feature = "serde_json")]
+        assert_one_newline(Format::Json);
+        #[cfg(feature = "serde_yaml")]
+        assert_one_newline(Format::Yaml);
+        #[cfg(feature = "ron")]
+        assert_one_newline(Format::Ron);
+        #[cfg(feature = "toml")]
+        assert_one_newline(Format::Toml);
+    }
+}
@@ -0,0 +1,196 @@
+//! INI files as [`StringTree`]s: the sections are dotted paths (e.g. `[server.tls]`), the keys may be dotted too (e.g. `tls.cert=...`)
+
+use crate::string_tree::{StringTree, join_path};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Parses the INI text (the backslash escapes and the continuation lines are handled by rust-ini)
+///
+/// The continuation lines are either escaped (ending with a backslash) or indented. The duplicate keys and the duplicate sections are merged (the last value wins).
+pub fn parse(input: &str) -> Result<StringTree, ini::ParseError> {
+    let options = ini::ParseOption {
+        enabled_quote: true,
+        enabled_escape: true,
+        enabled_indented_mutiline_value: true,
+        enabled_preserve_key_leading_whitespace: false,
+    };
+    let document = ini::Ini::load_from_str_opt(input, options)?;
+    let lines = key_lines(input);
+    let mut tree = StringTree::default();
+    for (section, properties) in &document {
+        let node = match section {
+            Some(section) => {
+                let node = tree.descendant_mut(section);
+                node.line = lines.get(&(Some(section), None)).copied();
+                node
+            }
+            None => &mut tree,
+        };
+        for (key, value) in properties {
+            node.insert(key, value.to_string(), lines.get(&(section, Some(key))).copied());
+        }
+    }
+    Ok(tree)
+}
+
+/// Finds the one-based lines of the section headers (the first occurrence) and the keys (the last occurrence), because rust-ini doesn't keep them
+///
+/// This is a best-effort scan: the escaped or quoted names that rust-ini decodes are not found, so their values have no line.
+fn key_lines(input: &str) -> HashMap<(Option<&str>, Option<&str>), usize> {
+    let mut lines = HashMap::new();
+    let mut section = None;
+    let mut is_continued = false;
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index.saturating_add(1);
+        let was_continued = std::mem::replace(&mut is_continued, line.ends_with('\\'));
+        let trimmed = line.trim();
+        if was_continued || line.starts_with(char::is_whitespace) || trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+            continue;
+        }
+        if let Some(header) = trimmed.strip_prefix('[') {
+            if let Some((name, _)) = header.split_once(']') {
+                section = Some(name.trim());
+                lines.entry((section, None)).or_insert(line_number);
+            }
+            continue;
+        }
+        if let Some(end) = trimmed.find(['=', ':']) {
+            lines.insert((section, Some(trimmed[..end].trim())), line_number);
+        }
+    }
+    lines
+}
+
+/// Writes the leaves of the root as the keys before the first section, the other nodes as the sections named after their dotted paths
+///
+/// The empty nodes (e.g. empty structs) are written as empty sections, so they are read back.
+pub fn write(writer: &mut impl Write, tree: &StringTree) -> std::io::Result<()> {
+    let mut is_first = true;
+    write_section(writer, None, tree, &mut is_first)
+}
+
+fn write_section(writer: &mut impl Write, path: Option<&str>, node: &StringTree, is_first: &mut bool) -> std::io::Result<()> {
+    let is_empty = node.children.is_empty();
+    let has_keys = node.children.iter().any(|(_, child)| child.value.is_some());
+    if let Some(path) = path {
+        if has_keys || is_empty {
+            if !*is_first {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "[{}]", escape(path, false))?;
+            *is_first = false;
+        }
+    }
+    for (key, child) in &node.children {
+        if let Some(value) = &child.value {
+            writeln!(writer, "{}={}", escape(key, true), escape_value(value))?;
+            *is_first = false;
+        }
+    }
+    for (key, child) in &node.children {
+        if !child.is_leaf() || child.value.is_none() {
+            write_section(writer, Some(&join_path(path, key)), child, is_first)?;
+        }
+    }
+    Ok(())
+}
+
+/// Escapes the backslash, the control characters, the comment and separator characters and the quotes (`is_key` also escapes a leading `[`, which would start a section)
+fn escape(input: &str, is_key: bool) -> String {
+    let mut output = String::with_capacity(input.len());
+    for (index, char) in input.char_indices() {
+        match char {
+            '\\' | ';' | '#' | '=' | ':' | '"' | '\'' | ']' => {
+                output.push('\\');
+                output.push(char);
+            }
+            '[' if is_key && index == 0 => output.push_str("\\["),
+            '\0' => output.push_str("\\0"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            '\n' => output.push_str("\\n"),
+            _ if char.is_ascii_control() => output.push_str(&format!("\\x{:04x}", u32::from(char))),
+            _ => output.push(char),
+        }
+    }
+    output
+}
+
+/// Quotes the values with leading or trailing whitespace (rust-ini trims the unquoted values)
+fn escape_value(value: &str) -> String {
+    let escaped = escape(value, false);
+    match value.trim() == value {
+        true => escaped,
+        false => format!("\"{escaped}\""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node<'a>(tree: &'a StringTree, path: &str) -> &'a StringTree {
+        path.split('.').fold(tree, |node, key| {
+            node.children
+                .iter()
+                .find(|(child_key, _)| child_key == key)
+                .map(|(_, child)| child)
+                .unwrap_or_else(|| panic!("no {key:?} in {node:?}"))
+        })
+    }
+
+    #[test]
+    fn must_nest_sections_and_dotted_keys() {
+        let tree = parse("name = app\n\n; comment\n[server]\nport = 80\ntls.cert = x.pem\n\n[server.limits]\nrate = 10\n").unwrap();
+        assert_eq!(node(&tree, "name").value.as_deref(), Some("app"));
+        assert_eq!(node(&tree, "server.port").value.as_deref(), Some("80"));
+        assert_eq!(node(&tree, "server.tls.cert").value.as_deref(), Some("x.pem"));
+        assert_eq!(node(&tree, "server.limits.rate").value.as_deref(), Some("10"));
+        assert_eq!(node(&tree, "server").line, Some(4));
+        assert_eq!(node(&tree, "server.port").line, Some(5));
+        assert_eq!(node(&tree, "server.limits.rate").line, Some(9));
+    }
+
+    #[test]
+    fn must_merge_duplicate_sections() {
+        let tree = parse("[a]\nx = 1\n[b]\ny = 2\n[a]\nx = 3\nz = 4\n").unwrap();
+        assert_eq!(node(&tree, "a.x").value.as_deref(), Some("3"));
+        assert_eq!(node(&tree, "a.z").value.as_deref(), Some("4"));
+        assert_eq!(node(&tree, "a.x").line, Some(6));
+    }
+
+    #[test]
+    fn must_read_quotes_escapes_and_continuations() {
+        let tree = parse("quoted = \"  padded  \"\nescaped = a\\;b\\nc\nlong = first \\\n  second\nindented = one\n  two\n").unwrap();
+        assert_eq!(node(&tree, "quoted").value.as_deref(), Some("  padded  "));
+        assert_eq!(node(&tree, "escaped").value.as_deref(), Some("a;b\nc"));
+        assert!(
+            node(&tree, "long")
+                .value
+                .as_deref()
+                .is_some_and(|value| value.starts_with("first") && value.ends_with("second"))
+        );
+        assert!(
+            node(&tree, "indented")
+                .value
+                .as_deref()
+                .is_some_and(|value| value.starts_with("one") && value.ends_with("two"))
+        );
+    }
+
+    #[test]
+    fn must_round_trip_tree() {
+        let mut tree = StringTree::default();
+        tree.insert("name", " padded ".to_string(), None);
+        tree.insert("server.port", "80".to_string(), None);
+        tree.insert("server.tls.cert", "a;b=c\"d\\e".to_string(), None);
+        tree.insert("servers.0.host", "[x]".to_string(), None);
+        tree.descendant_mut("empty");
+        let mut output = Vec::new();
+        write(&mut output, &tree).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("name=\" padded \"\n\n[server]\nport=80\n"), "{output}");
+        let parsed = parse(&output).unwrap();
+        assert_eq!(parsed.clone().into_entries(), tree.into_entries(), "{output}");
+    }
+}
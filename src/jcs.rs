@@ -0,0 +1,219 @@
+//! JSON Canonicalization Scheme ([RFC 8785](https://www.rfc-editor.org/rfc/rfc8785))
+
+use crate::canonical_value::CanonicalValue;
+use crate::errors::canonicalize_error::CanonicalizeError;
+use serde::ser::Error;
+use std::fmt::Write;
+
+/// Renders the value as canonical JSON: no whitespace, object members sorted by the UTF-16 code units of their names, numbers formatted like ECMAScript `Number.prototype.toString`
+///
+/// Every number is converted to an IEEE 754 double first, as RFC 8785 requires: the integers beyond ±2^53 are rounded (e.g. `9007199254740993` is written as `9007199254740992`), and an `f32` is written with the digits of its exact double value (e.g. `0.1f32` is written as `0.10000000149011612`).
+pub fn to_string(value: &CanonicalValue) -> Result<String, CanonicalizeError> {
+    let mut output = String::new();
+    write_value(value, &mut output)?;
+    Ok(output)
+}
+
+fn write_value(value: &CanonicalValue, output: &mut String) -> Result<(), CanonicalizeError> {
+    use CanonicalValue::*;
+    match value {
+        Unit | None | UnitStruct(_) => output.push_str("null"),
+        Bool(value) => output.push_str(if *value { "true" } else { "false" }),
+        I64(value) => output.push_str(&format_number(*value as f64)?),
+        U64(value) => output.push_str(&format_number(*value as f64)?),
+        I128(value) => output.push_str(&format_number(*value as f64)?),
+        U128(value) => output.push_str(&format_number(*value as f64)?),
+        F32(value) => output.push_str(&format_number(f64::from(*value))?),
+        F64(value) => output.push_str(&format_number(*value)?),
+        Char(value) => write_string(&value.to_string(), output),
+        Str(value) => write_string(value, output),
+        Bytes(bytes) => {
+            let items = bytes.iter().map(u8::to_string).collect::<Vec<_>>();
+            push_display(output, format_args!("[{}]", items.join(",")))
+        }
+        Some(value) | NewtypeStruct(_, value) => write_value(value, output)?,
+        UnitVariant(variant) => write_string(variant.variant, output),
+        NewtypeVariant(variant, value) => write_variant(variant.variant, output, |output| write_value(value, output))?,
+        Seq(items) | Tuple(items) | TupleStruct(_, items) => write_array(items.iter(), output)?,
+        TupleVariant(variant, items) => write_variant(variant.variant, output, |output| write_array(items.iter(), output))?,
+        Map(entries) => {
+            let members = entries
+                .iter()
+                .map(|(key, value)| Ok((key_to_string(key)?, value)))
+                .collect::<Result<Vec<_>, CanonicalizeError>>()?;
+            write_object(members, output)?
+        }
+        Struct(_, fields) => write_object(fields_to_members(fields), output)?,
+        StructVariant(variant, fields) => write_variant(variant.variant, output, |output| write_object(fields_to_members(fields), output))?,
+    }
+    Ok(())
+}
+
+fn push_display(output: &mut String, value: impl std::fmt::Display) {
+    write!(output, "{value}").expect("writing into a String must succeed");
+}
+
+fn fields_to_members<'a>(fields: &'a [(&'static str, CanonicalValue)]) -> Vec<(String, &'a CanonicalValue)> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+fn key_to_string(key: &CanonicalValue) -> Result<String, CanonicalizeError> {
+    use CanonicalValue::*;
+    match key {
+        Str(key) => Ok(key.clone()),
+        Char(key) => Ok(key.to_string()),
+        Bool(_) | I64(_) | U64(_) | I128(_) | U128(_) | F32(_) | F64(_) => to_string(key),
+        UnitVariant(variant) => Ok(variant.variant.to_string()),
+        Some(key) | NewtypeStruct(_, key) => key_to_string(key),
+        _ => Err(CanonicalizeError::custom("key must be a string")),
+    }
+}
+
+/// Writes an externally tagged enum variant (`{"variant":...}`)
+fn write_variant(variant: &str, output: &mut String, write_content: impl FnOnce(&mut String) -> Result<(), CanonicalizeError>) -> Result<(), CanonicalizeError> {
+    output.push('{');
+    write_string(variant, output);
+    output.push(':');
+    write_content(output)?;
+    output.push('}');
+    Ok(())
+}
+
+fn write_array<'a>(items: impl Iterator<Item = &'a CanonicalValue>, output: &mut String) -> Result<(), CanonicalizeError> {
+    output.push('[');
+    for (index, item) in items.enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_value(item, output)?;
+    }
+    output.push(']');
+    Ok(())
+}
+
+fn write_object(mut members: Vec<(String, &CanonicalValue)>, output: &mut String) -> Result<(), CanonicalizeError> {
+    members.sort_by(|(left, _), (right, _)| left.encode_utf16().cmp(right.encode_utf16()));
+    output.push('{');
+    for (index, (key, value)) in members.into_iter().enumerate() {
+        if index != 0 {
+            output.push(',');
+        }
+        write_string(&key, output);
+        output.push(':');
+        write_value(value, output)?;
+    }
+    output.push('}');
+    Ok(())
+}
+
+fn write_string(string: &str, output: &mut String) {
+    output.push('"');
+    for char in string.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            '\u{0}'..='\u{1f}' => push_display(output, format_args!("\\u{:04x}", u32::from(char))),
+            _ => output.push(char),
+        }
+    }
+    output.push('"');
+}
+
+/// Formats the number like ECMAScript `Number.prototype.toString`, starting from the shortest round-trip representation in the scientific notation (e.g. `1.25e-7`)
+fn format_number(value: f64) -> Result<String, CanonicalizeError> {
+    if !value.is_finite() {
+        return Err(CanonicalizeError::custom("NaN and infinite numbers are not allowed"));
+    }
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .ok_or_else(|| CanonicalizeError::custom(format!("unexpected number format: {scientific}")))?;
+    let digits = mantissa.replace('.', "");
+    if digits == "0" {
+        return Ok("0".to_string());
+    }
+    let exponent = exponent.parse::<i64>().map_err(CanonicalizeError::custom)?;
+    let digits_count = i64::try_from(digits.len()).map_err(CanonicalizeError::custom)?;
+    // The position of the decimal point relative to the start of the digits
+    let point = exponent.saturating_add(1);
+    let zeros = |count: i64| "0".repeat(usize::try_from(count).unwrap_or_default());
+    let (integer_digits, fraction_digits) = digits.split_at(usize::try_from(point.clamp(0, digits_count)).unwrap_or_default());
+    let body = if digits_count <= point && point <= 21 {
+        format!("{digits}{}", zeros(point.saturating_sub(digits_count)))
+    } else if 0 < point && point <= 21 {
+        format!("{integer_digits}.{fraction_digits}")
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{digits}", zeros(point.saturating_neg()))
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { String::new() } else { format!(".{rest}") };
+        format!("{first}{rest}e{sign}{}", exponent.unsigned_abs())
+    };
+    Ok(if value.is_sign_negative() { format!("-{body}") } else { body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    fn canonical<T: Serialize>(value: &T) -> String {
+        to_string(&CanonicalValue::from_serialize(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn must_format_numbers_like_ecmascript() {
+        // The examples of RFC 8785 (appendix B)
+        assert_eq!(canonical(&0.0), "0");
+        assert_eq!(canonical(&-0.0), "0");
+        assert_eq!(canonical(&5e-324), "5e-324");
+        assert_eq!(canonical(&1.7976931348623157e308), "1.7976931348623157e+308");
+        assert_eq!(canonical(&9007199254740992.0), "9007199254740992");
+        assert_eq!(canonical(&295147905179352830000.0), "295147905179352830000");
+        assert_eq!(canonical(&1e21), "1e+21");
+        assert_eq!(canonical(&1e-7), "1e-7");
+        assert_eq!(canonical(&-1e-7), "-1e-7");
+        assert_eq!(canonical(&0.000001), "0.000001");
+        assert_eq!(canonical(&333333333.3333333), "333333333.3333333");
+        assert_eq!(canonical(&4.5), "4.5");
+        assert_eq!(canonical(&2e-3), "0.002");
+        assert_eq!(canonical(&1e-27), "1e-27");
+    }
+
+    #[test]
+    fn must_convert_integers_and_f32_to_f64() {
+        assert_eq!(canonical(&9007199254740993u64), "9007199254740992");
+        assert_eq!(canonical(&-9007199254740993i64), "-9007199254740992");
+        assert_eq!(canonical(&u128::from(u64::MAX)), "18446744073709552000");
+        assert_eq!(canonical(&42u8), "42");
+        assert_eq!(canonical(&0.1f32), "0.10000000149011612");
+    }
+
+    #[test]
+    fn must_reject_non_finite_numbers() {
+        assert!(to_string(&CanonicalValue::from_serialize(&f64::NAN).unwrap()).is_err());
+        assert!(to_string(&CanonicalValue::from_serialize(&f64::INFINITY).unwrap()).is_err());
+    }
+
+    #[test]
+    fn must_sort_members_by_utf16_code_units() {
+        // U+1F600 is a surrogate pair (0xD83D 0xDE00), so it sorts before U+FB33 in UTF-16 (but not in UTF-8)
+        let map = BTreeMap::from([("\u{fb33}", 1), ("\u{1f600}", 2), ("b", 3), ("a", 4)]);
+        assert_eq!(canonical(&map), "{\"a\":4,\"b\":3,\"\u{1f600}\":2,\"\u{fb33}\":1}");
+    }
+
+    #[test]
+    fn must_escape_only_the_required_characters() {
+        assert_eq!(canonical(&"\u{8}\t\n\u{c}\r\u{1f}\"\\/\u{e9}"), "\"\\b\\t\\n\\f\\r\\u001f\\\"\\\\/\u{e9}\"");
+    }
+}
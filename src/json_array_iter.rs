@@ -0,0 +1,388 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use crate::location::Location;
+use serde::de::DeserializeOwned;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// Lazily deserializes the elements of a top-level JSON array
+///
+/// Only the bytes of the current element are kept in memory: the iterator finds the element boundaries by tracking the nesting depth and string literals, then passes each element to [`serde_json::from_slice`].
+/// An invalid element yields an error without stopping the iteration; a malformed array (e.g. a missing `[` or an unexpected end of input) yields an error and stops it.
+pub struct JsonArrayIter<R, T> {
+    reader: R,
+    state: JsonArrayIterState,
+    buffer: Vec<u8>,
+    /// 0-based line of the current position in the input
+    line: usize,
+    /// 0-based column (in characters) of the current position in the input
+    column: usize,
+    phantom: PhantomData<T>,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum JsonArrayIterState {
+    Start,
+    Elements,
+    Trailing,
+    Finished,
+}
+
+impl<R: BufRead, T: DeserializeOwned> JsonArrayIter<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            state: JsonArrayIterState::Start,
+            buffer: Vec::new(),
+            line: 0,
+            column: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<u8>, DeserializeOneError> {
+        loop {
+            let chunk = self
+                .reader
+                .fill_buf()
+                .map_err(DeserializeOneErrorKind::Io)?;
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+            match chunk
+                .iter()
+                .position(|byte| !matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+            {
+                Some(position) => {
+                    let byte = chunk[position];
+                    advance(&mut self.line, &mut self.column, &chunk[..position]);
+                    self.reader.consume(position);
+                    return Ok(Some(byte));
+                }
+                None => {
+                    let len = chunk.len();
+                    advance(&mut self.line, &mut self.column, chunk);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    fn expect_end_of_input(&mut self) -> Result<(), DeserializeOneError> {
+        match self.skip_whitespace()? {
+            None => Ok(()),
+            Some(byte) => Err(syntax_error(format!("expected end of input after the top-level array, found {:?}", char::from(byte)))),
+        }
+    }
+
+    fn start(&mut self) -> Result<bool, DeserializeOneError> {
+        match self.skip_whitespace()? {
+            Some(b'[') => self.consume_byte(),
+            Some(byte) => return Err(syntax_error(format!("expected a top-level array, found {:?}", char::from(byte)))),
+            None => return Err(syntax_error("expected a top-level array, found end of input")),
+        }
+        match self.skip_whitespace()? {
+            Some(b']') => {
+                self.consume_byte();
+                Ok(false)
+            }
+            Some(_) => Ok(true),
+            None => Err(syntax_error("unexpected end of input inside the top-level array")),
+        }
+    }
+
+    fn consume_byte(&mut self) {
+        self.reader.consume(1);
+        self.column = self.column.saturating_add(1);
+    }
+
+    /// Deserializes the element in `self.buffer`, translating the error location from the element to the whole input
+    fn deserialize_element(&self, line: usize, column: usize) -> Result<T, DeserializeOneError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(&self.buffer);
+        let output = serde_path_to_error::deserialize(&mut deserializer)
+            .map_err(DeserializeOneError::from_path_to_error)
+            .and_then(|output| {
+                deserializer
+                    .end()
+                    .map(|()| output)
+                    .map_err(DeserializeOneError::from)
+            });
+        output.map_err(|error| {
+            // serde_json counts the column in bytes
+            let mut error = error.with_byte_column(&self.buffer);
+            if let Some(Location {
+                line: Some(element_line),
+                column: Some(element_column),
+                ..
+            }) = error.location
+            {
+                let is_first_line = element_line <= 1;
+                error.location = Some(Location {
+                    line: Some(line.saturating_add(element_line)),
+                    column: Some(if is_first_line { column.saturating_add(element_column) } else { element_column }),
+                    offset: None,
+                });
+            }
+            error
+        })
+    }
+
+    /// Reads the bytes of the next element into `self.buffer`, returns `true` if the element is the last one
+    fn read_element(&mut self) -> Result<bool, DeserializeOneError> {
+        self.buffer.clear();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let chunk = self
+                .reader
+                .fill_buf()
+                .map_err(DeserializeOneErrorKind::Io)?;
+            if chunk.is_empty() {
+                return Err(syntax_error("unexpected end of input inside the top-level array"));
+            }
+            let mut terminator = None;
+            for (index, &byte) in chunk.iter().enumerate() {
+                if in_string {
+                    if escaped {
+                        escaped = false;
+                    } else if byte == b'\\' {
+                        escaped = true;
+                    } else if byte == b'"' {
+                        in_string = false;
+                    }
+                    continue;
+                }
+                match byte {
+                    b'"' => in_string = true,
+                    b'[' | b'{' => depth = depth.saturating_add(1),
+                    b']' | b'}' => match depth.checked_sub(1) {
+                        Some(value) => depth = value,
+                        None => {
+                            terminator = Some((index, byte));
+                            break;
+                        }
+                    },
+                    b',' if depth == 0 => {
+                        terminator = Some((index, byte));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match terminator {
+                Some((index, byte)) => {
+                    let consumed = index.saturating_add(1);
+                    self.buffer.extend_from_slice(&chunk[..index]);
+                    advance(&mut self.line, &mut self.column, &chunk[..consumed]);
+                    self.reader.consume(consumed);
+                    return match byte {
+                        b',' => Ok(false),
+                        b']' => Ok(true),
+                        _ => Err(syntax_error(format!("unexpected {:?} inside the top-level array", char::from(byte)))),
+                    };
+                }
+                None => {
+                    let len = chunk.len();
+                    self.buffer.extend_from_slice(chunk);
+                    advance(&mut self.line, &mut self.column, chunk);
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for JsonArrayIter<R, T> {
+    type Item = Result<T, DeserializeOneError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == JsonArrayIterState::Start {
+            match self.start() {
+                Ok(true) => self.state = JsonArrayIterState::Elements,
+                Ok(false) => self.state = JsonArrayIterState::Trailing,
+                Err(error) => {
+                    self.state = JsonArrayIterState::Finished;
+                    return Some(Err(error));
+                }
+            }
+        }
+        match self.state {
+            JsonArrayIterState::Trailing => {
+                self.state = JsonArrayIterState::Finished;
+                return self.expect_end_of_input().err().map(Err);
+            }
+            JsonArrayIterState::Finished => return None,
+            JsonArrayIterState::Start | JsonArrayIterState::Elements => {}
+        }
+        let (line, column) = (self.line, self.column);
+        match self.read_element() {
+            Ok(is_last) => {
+                if is_last {
+                    self.state = JsonArrayIterState::Trailing;
+                }
+                Some(self.deserialize_element(line, column))
+            }
+            Err(error) => {
+                self.state = JsonArrayIterState::Finished;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+fn advance(line: &mut usize, column: &mut usize, bytes: &[u8]) {
+    for byte in bytes {
+        if *byte == b'\n' {
+            *line = line.saturating_add(1);
+            *column = 0;
+        } else if !is_utf8_continuation(*byte) {
+            *column = column.saturating_add(1);
+        }
+    }
+}
+
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+fn syntax_error(message: impl std::fmt::Display) -> DeserializeOneError {
+    <serde_json::Error as serde::de::Error>::custom(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize, PartialEq, Debug)]
+    struct Server {
+        port: u16,
+    }
+
+    /// Reads the input one byte at a time, so that every element crosses the buffer boundaries
+    fn iter<T: DeserializeOwned>(input: &[u8]) -> JsonArrayIter<std::io::BufReader<&[u8]>, T> {
+        JsonArrayIter::new(std::io::BufReader::with_capacity(1, input))
+    }
+
+    #[test]
+    fn must_split_strings_with_delimiters_and_escaped_quotes() {
+        let input = br#"["a[b", "c]d", "e,f", "g\"],h", "i\\", "{j}"]"#;
+        let items = iter::<String>(input)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(items, vec!["a[b", "c]d", "e,f", "g\"],h", "i\\", "{j}"]);
+    }
+
+    #[test]
+    fn must_read_nested_arrays_and_objects() {
+        let input = br#"[ [1, [2, 3]], {"a": [4, {"b": 5}]}, [] , {} ]"#;
+        let items = iter::<serde_json::Value>(input)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            items,
+            vec![
+                serde_json::json!([1, [2, 3]]),
+                serde_json::json!({"a": [4, {"b": 5}]}),
+                serde_json::json!([]),
+                serde_json::json!({})
+            ]
+        );
+    }
+
+    #[test]
+    fn must_read_empty_arrays() {
+        assert!(iter::<u8>(b"[]").next().is_none());
+        assert!(iter::<u8>(b" \n[ \n ] \n").next().is_none());
+    }
+
+    #[test]
+    fn must_reject_trailing_comma() {
+        let items = iter::<u8>(b"[1, 2,]").collect::<Vec<_>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap(), &1);
+        assert_eq!(items[1].as_ref().unwrap(), &2);
+        assert!(items[2].is_err());
+    }
+
+    #[test]
+    fn must_yield_invalid_element_and_continue() {
+        let items = iter::<Server>(br#"[{"port": 1}, {"port": "x"}, {"port": 3}]"#).collect::<Vec<_>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0].as_ref().unwrap(),
+            &Server {
+                port: 1
+            }
+        );
+        assert_eq!(items[1].as_ref().unwrap_err().field_path.as_deref(), Some("port"));
+        assert_eq!(
+            items[2].as_ref().unwrap(),
+            &Server {
+                port: 3
+            }
+        );
+    }
+
+    #[test]
+    fn must_stop_on_truncated_input() {
+        for input in [b"[1, 2".as_slice(), b"[1, {\"a\": [2,", b"[1, \"a]", b"["] {
+            let items = iter::<serde_json::Value>(input).collect::<Vec<_>>();
+            let error = items.last().unwrap().as_ref().unwrap_err();
+            assert!(error.to_string().contains("unexpected end of input"), "{error}");
+            assert!(
+                items[..items.len().saturating_sub(1)]
+                    .iter()
+                    .all(Result::is_ok)
+            );
+        }
+    }
+
+    #[test]
+    fn must_reject_input_that_is_not_an_array() {
+        let items = iter::<u8>(b"{\"a\": 1}").collect::<Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert!(
+            items[0]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("expected a top-level array")
+        );
+        let items = iter::<u8>(b"[1] 2").collect::<Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert!(
+            items[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("expected end of input")
+        );
+    }
+
+    #[test]
+    fn must_map_error_location_to_document() {
+        let input = "[\n  {\"port\": 1},\n  {\"port\": \"x\"},\n  {\"ü\": 0, \"port\":\n\"y\"}\n]".as_bytes();
+        let document_error = crate::format::Format::Json
+            .deserialize_one_from_slice::<Vec<Server>>(input)
+            .unwrap_err();
+        let errors = iter::<Server>(input)
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0]
+                .location
+                .map(|location| (location.line, location.column)),
+            document_error
+                .location
+                .map(|location| (location.line, location.column))
+        );
+        assert_eq!(errors[0].to_string(), "3:14: port: invalid type: string \"x\", expected u16");
+        assert_eq!(errors[1].to_string(), "5:3: port: invalid type: string \"y\", expected u16");
+    }
+}
@@ -0,0 +1,226 @@
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, Error, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt::Formatter;
+
+/// The key of the single-entry map that serde_json passes a number as with its `arbitrary_precision` feature (enabled by the `cli` feature), if the number doesn't fit `i64`/`u64` or has a fraction or an exponent
+pub const JSON_NUMBER_KEY: &str = "$serde_json::private::Number";
+
+/// Visits the number as the narrowest type that holds it exactly (the integers out of the 128-bit range are refused instead of being rounded)
+pub fn visit_number<'de, V: Visitor<'de>, E: Error>(number: &str, visitor: V) -> Result<V::Value, E> {
+    if number.contains(['.', 'e', 'E']) {
+        return visitor.visit_f64(number.parse().map_err(E::custom)?);
+    }
+    if let Ok(value) = number.parse() {
+        return visitor.visit_i128(value);
+    }
+    if let Ok(value) = number.parse() {
+        return visitor.visit_u128(value);
+    }
+    Err(E::custom(format!("the integer {number} is out of the range of 128-bit integers")))
+}
+
+/// Passes the numbers of a serde_json deserializer as numbers (see [`visit_number`]) to a visitor of any value (e.g. to a transcoder, which would write them as maps otherwise)
+///
+/// serde_json never calls `visit_enum` from `deserialize_any`, so the enums are passed through unchanged.
+pub struct JsonNumbers<D>(pub D);
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for JsonNumbers<D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_any(NumbersVisitor(visitor))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct NumbersVisitor<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for NumbersVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, value: bool) -> Result<Self::Value, E> {
+        self.0.visit_bool(value)
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.0.visit_i64(value)
+    }
+
+    fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+        self.0.visit_i128(value)
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.0.visit_u64(value)
+    }
+
+    fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+        self.0.visit_u128(value)
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+        self.0.visit_f64(value)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        self.0.visit_str(value)
+    }
+
+    fn visit_borrowed_str<E: Error>(self, value: &'de str) -> Result<Self::Value, E> {
+        self.0.visit_borrowed_str(value)
+    }
+
+    fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+        self.0.visit_string(value)
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        self.0.visit_bytes(value)
+    }
+
+    fn visit_borrowed_bytes<E: Error>(self, value: &'de [u8]) -> Result<Self::Value, E> {
+        self.0.visit_borrowed_bytes(value)
+    }
+
+    fn visit_byte_buf<E: Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        self.0.visit_byte_buf(value)
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(JsonNumbers(deserializer))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_newtype_struct(JsonNumbers(deserializer))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(NumbersAccess {
+            inner: seq,
+            first_key: None,
+        })
+    }
+
+    /// The first key tells whether the map is a number, so it is read here and passed on to the visitor before the other keys
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let key = map.next_key::<String>()?;
+        if key.as_deref() == Some(JSON_NUMBER_KEY) {
+            let number = map.next_value::<String>()?;
+            return visit_number(&number, self.0);
+        }
+        self.0.visit_map(NumbersAccess {
+            inner: map,
+            first_key: Some(key),
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_enum(data)
+    }
+}
+
+struct NumbersAccess<A> {
+    inner: A,
+    /// The key already read from the map (`Some(None)` if the map is empty)
+    first_key: Option<Option<String>>,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for NumbersAccess<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        self.inner.next_element_seed(NumbersSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for NumbersAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.first_key.take() {
+            Some(Some(key)) => seed.deserialize(key.into_deserializer()).map(Some),
+            Some(None) => {
+                self.first_key = Some(None);
+                Ok(None)
+            }
+            None => self.inner.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        self.inner.next_value_seed(NumbersSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+struct NumbersSeed<T>(T);
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for NumbersSeed<T> {
+    type Value = T::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(JsonNumbers(deserializer))
+    }
+}
+
+/// The numbers are passed as maps only with `arbitrary_precision`, which the `cli` feature enables
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use super::*;
+    use crate::transcode_value::TranscodeValue;
+    use serde::Deserialize;
+
+    fn read(input: &str) -> TranscodeValue {
+        TranscodeValue::deserialize(JsonNumbers(&mut serde_json::Deserializer::from_str(input))).unwrap()
+    }
+
+    #[test]
+    fn must_keep_numbers_exact() {
+        assert_eq!(
+            read("[1, -1, 0.5, 1e2]"),
+            TranscodeValue::Seq(vec![
+                TranscodeValue::U64(1),
+                TranscodeValue::I64(-1),
+                TranscodeValue::F64(0.5),
+                TranscodeValue::F64(100.0)
+            ])
+        );
+        assert_eq!(read("{\"a\": 18446744073709551616}"), TranscodeValue::Map(vec![(TranscodeValue::Str("a".into()), TranscodeValue::I128(18446744073709551616))]));
+        assert_eq!(read("{}"), TranscodeValue::Map(vec![]));
+    }
+
+    #[test]
+    fn must_refuse_integers_out_of_range() {
+        let input = format!("[{}0]", u128::MAX);
+        let error = TranscodeValue::deserialize(JsonNumbers(&mut serde_json::Deserializer::from_str(&input))).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("out of the range of 128-bit integers"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::io::{self, Write};
+
+/// Remembers the last written byte (to check whether the output ends with a newline without buffering it)
+#[derive(Debug)]
+pub struct LastByteWriter<W> {
+    pub inner: W,
+    pub last_byte: Option<u8>,
+}
+
+impl<W> LastByteWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            last_byte: None,
+        }
+    }
+}
+
+impl<W: Write> Write for LastByteWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(byte) = buf.get(..written).and_then(<[u8]>::last) {
+            self.last_byte = Some(*byte);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, VariantArray};
+
+/// Whether the output is indented
+#[derive(Serialize, Deserialize, Display, VariantArray, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Layout {
+    /// The default layout of the format (pretty for JSON, compact for XML)
+    #[default]
+    Native,
+    /// No whitespace between the tokens (one line for JSON and XML)
+    Compact,
+    /// Indented with [`FormatOptions::indent_width`](crate::format_options::FormatOptions::indent_width) copies of [`FormatOptions::indent_char`](crate::format_options::FormatOptions::indent_char)
+    Pretty,
+}
+
+impl Layout {
+    pub fn is_pretty(self, native_is_pretty: bool) -> bool {
+        match self {
+            Layout::Native => native_is_pretty,
+            Layout::Compact => false,
+            Layout::Pretty => true,
+        }
+    }
+}
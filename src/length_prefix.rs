@@ -0,0 +1,55 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use std::io::{Read, Write};
+
+/// Writes the length of the item (a little-endian `u32`) followed by the item
+pub fn write_length_prefixed(writer: &mut impl Write, item: &[u8]) -> std::io::Result<()> {
+    let length = u32::try_from(item.len()).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "the item is longer than u32::MAX bytes"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(item)
+}
+
+/// Reads an item written by [`write_length_prefixed`]
+pub fn read_length_prefixed(reader: &mut impl Read) -> Result<Vec<u8>, DeserializeOneError> {
+    let mut length = [0; 4];
+    reader
+        .read_exact(&mut length)
+        .map_err(DeserializeOneErrorKind::Io)?;
+    let length = u32::from_le_bytes(length);
+    let mut item = Vec::new();
+    let read = reader
+        .take(u64::from(length))
+        .read_to_end(&mut item)
+        .map_err(DeserializeOneErrorKind::Io)?;
+    if read != length as usize {
+        return Err(DeserializeOneErrorKind::Io(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, format!("the item is truncated: expected {length} bytes, found {read}"))).into());
+    }
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_round_trip_items() {
+        let mut output = Vec::new();
+        for item in [b"abc".as_slice(), b"", &[0; 300]] {
+            write_length_prefixed(&mut output, item).unwrap();
+        }
+        assert_eq!(output[..7], [3, 0, 0, 0, b'a', b'b', b'c']);
+        let mut reader = output.as_slice();
+        assert_eq!(read_length_prefixed(&mut reader).unwrap(), b"abc");
+        assert_eq!(read_length_prefixed(&mut reader).unwrap(), b"");
+        assert_eq!(read_length_prefixed(&mut reader).unwrap(), vec![0; 300]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn must_reject_truncated_input() {
+        let error = read_length_prefixed(&mut [3, 0].as_slice()).unwrap_err();
+        assert!(matches!(*error.source, DeserializeOneErrorKind::Io(ref error) if error.kind() == std::io::ErrorKind::UnexpectedEof));
+        let error = read_length_prefixed(&mut [3, 0, 0, 0, b'a'].as_slice()).unwrap_err();
+        assert!(error.to_string().contains("expected 3 bytes, found 1"), "{error}");
+    }
+}
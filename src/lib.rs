@@ -1,5 +1,101 @@
 #![deny(clippy::arithmetic_side_effects)]
 #![cfg_attr(not(test), deny(unused_crate_dependencies))]
 
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod append_many_options;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+pub mod binary_header;
+pub mod canonical_value;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "cli")]
+pub mod cli_command;
+#[cfg(feature = "cli")]
+pub mod cli_io;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+pub mod compact_codec;
+pub mod compression;
+#[cfg(any(feature = "ciborium", feature = "rmp-serde", feature = "bincode", feature = "postcard"))]
+pub mod concatenated_items_iter;
+#[cfg(feature = "cli")]
+pub mod convert_command;
+#[cfg(any(feature = "quick-xml", feature = "serde-xml-rs", feature = "ciborium"))]
+pub mod counting_reader;
+#[cfg(feature = "csv")]
+pub mod csv_options;
+#[cfg(feature = "csv")]
+pub mod csv_quote_style;
+#[cfg(all(feature = "async", feature = "csv"))]
+pub mod csv_records_stream;
+#[cfg(feature = "csv")]
+pub mod csv_terminator;
+pub mod detect;
 pub mod errors;
+#[cfg(feature = "lock")]
+pub mod file_lock;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod flush_policy;
+#[cfg(feature = "cli")]
+pub mod fmt_command;
 pub mod format;
+#[cfg(feature = "async")]
+pub mod format_async;
+pub mod format_guess;
+#[cfg(feature = "lock")]
+pub mod format_locked;
+pub mod format_options;
+#[cfg(feature = "rust-ini")]
+pub mod ini_tree;
+#[cfg(any(feature = "serde_json", feature = "serde-jsonlines"))]
+pub mod jcs;
+#[cfg(feature = "serde_json")]
+pub mod json_array_iter;
+#[cfg(all(feature = "serde-transcode", feature = "serde_json"))]
+pub mod json_numbers;
+pub mod last_byte_writer;
+pub mod layout;
+#[cfg(any(feature = "bincode", feature = "postcard"))]
+pub mod length_prefix;
+pub mod location;
+#[cfg(feature = "lock")]
+pub mod lock_options;
+#[cfg(feature = "serde-transcode")]
+pub mod loss_check;
+#[cfg(feature = "serde-transcode")]
+pub mod lossy_value_kind;
+pub mod many_options;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod many_writer;
+pub mod operation;
+#[cfg(feature = "properties")]
+pub mod properties;
+#[cfg(feature = "miette")]
+pub mod render_diagnostic;
+pub mod save_mode;
+pub mod save_one_options;
+#[cfg(feature = "miette")]
+pub mod source_text;
+#[cfg(feature = "async")]
+pub mod spawn_blocking;
+#[cfg(feature = "string-tree")]
+pub mod string_tree;
+#[cfg(feature = "string-tree")]
+pub mod string_tree_deserializer;
+#[cfg(feature = "string-tree")]
+pub mod string_tree_serializer;
+pub mod string_writer;
+#[cfg(any(feature = "serde-jsonlines", feature = "csv"))]
+pub mod sync_policy;
+#[cfg(feature = "serde-transcode")]
+pub mod transcode;
+#[cfg(feature = "serde-transcode")]
+pub mod transcode_value;
+#[cfg(feature = "lock")]
+pub mod update_one_options;
+#[cfg(feature = "cli")]
+pub mod validate_command;
+pub mod write_atomically;
+#[cfg(feature = "quick-xml")]
+pub mod xml_elements_iter;
+#[cfg(feature = "serde_yaml")]
+pub mod yaml_documents_iter;
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Position of an error in the input
+///
+/// `line` and `column` are 1-based, `offset` is a 0-based byte offset. Each of them is `None` if the backend doesn't report it.
+#[derive(Serialize, Deserialize, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct Location {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl Location {
+    pub fn from_offset(input: &[u8], offset: usize) -> Self {
+        let prefix = input.get(..offset).unwrap_or(input);
+        let line_start = prefix
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |index| index.saturating_add(1));
+        let line = prefix
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count()
+            .saturating_add(1);
+        let line_prefix = &prefix[line_start..];
+        let column = match std::str::from_utf8(line_prefix) {
+            Ok(line_prefix) => line_prefix.chars().count(),
+            Err(_) => line_prefix.len(),
+        };
+        Self {
+            line: Some(line),
+            column: Some(column.saturating_add(1)),
+            offset: Some(offset),
+        }
+    }
+
+    /// Resolves the location from a 1-based line and a 1-based column that counts the bytes instead of the characters (e.g. serde_json)
+    pub fn from_byte_column(input: &[u8], line: usize, column: usize) -> Self {
+        let line_start = match line.checked_sub(2) {
+            None => 0,
+            Some(index) => input
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+                .nth(index)
+                .map_or(input.len(), |(index, _)| index.saturating_add(1)),
+        };
+        Self::from_offset(input, line_start.saturating_add(column.saturating_sub(1)))
+    }
+
+    /// Returns the byte offset, computing it from the line and the column if the backend didn't report it
+    pub fn to_offset(&self, input: &str) -> Option<usize> {
+        if let Some(offset) = self.offset {
+            return Some(offset.min(input.len()));
+        }
+        let line_index = self.line?.checked_sub(1)?;
+        let line_start = if line_index == 0 {
+            0
+        } else {
+            input
+                .match_indices('\n')
+                .nth(line_index.saturating_sub(1))
+                .map(|(index, _)| index.saturating_add(1))?
+        };
+        let line = input[line_start..].split('\n').next().unwrap_or_default();
+        let column_index = self.column.unwrap_or(1).saturating_sub(1);
+        let column_offset = line
+            .char_indices()
+            .nth(column_index)
+            .map_or(line.len(), |(index, _)| index);
+        Some(line_start.saturating_add(column_offset))
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column, self.offset) {
+            (Some(line), Some(column), _) => write!(f, "{line}:{column}"),
+            (Some(line), None, _) => write!(f, "{line}"),
+            (None, _, Some(offset)) => write!(f, "byte {offset}"),
+            (None, _, None) => write!(f, "unknown location"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_resolve_line_and_column_from_offset() {
+        let input = "a: 1\nbé: x\n";
+        assert_eq!(
+            Location::from_offset(input.as_bytes(), 0),
+            Location {
+                line: Some(1),
+                column: Some(1),
+                offset: Some(0),
+            }
+        );
+        // The column counts the characters, not the bytes
+        let offset = input.find('x').unwrap();
+        assert_eq!(
+            Location::from_offset(input.as_bytes(), offset),
+            Location {
+                line: Some(2),
+                column: Some(5),
+                offset: Some(offset),
+            }
+        );
+    }
+
+    #[test]
+    fn must_resolve_column_from_byte_column() {
+        let input = "a: 1\nbé: x\n";
+        assert_eq!(Location::from_byte_column(input.as_bytes(), 2, 6), Location::from_offset(input.as_bytes(), input.find('x').unwrap()));
+        assert_eq!(Location::from_byte_column(input.as_bytes(), 1, 1), Location::from_offset(input.as_bytes(), 0));
+    }
+
+    #[test]
+    fn must_resolve_offset_from_line_and_column() {
+        let input = "a: 1\nbé: x\n";
+        let location = Location {
+            line: Some(2),
+            column: Some(5),
+            offset: None,
+        };
+        assert_eq!(location.to_offset(input), input.find('x'));
+        let location = Location {
+            line: Some(9),
+            column: Some(1),
+            offset: None,
+        };
+        assert_eq!(location.to_offset(input), None);
+    }
+
+    #[test]
+    fn must_display_known_parts() {
+        let location = Location {
+            line: Some(14),
+            column: Some(7),
+            offset: None,
+        };
+        assert_eq!(location.to_string(), "14:7");
+        let location = Location {
+            line: None,
+            column: None,
+            offset: Some(3),
+        };
+        assert_eq!(location.to_string(), "byte 3");
+    }
+}
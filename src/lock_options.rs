@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+/// Options for [`FileLock`](crate::file_lock::FileLock) (e.g. [`Format::load_one_locked_with`](crate::format::Format::load_one_locked_with))
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct LockOptions {
+    /// How long to wait for the other processes to release the lock (`None` means waiting forever)
+    pub timeout: Option<Duration>,
+    /// How long to sleep between the attempts to take the lock
+    pub poll_interval: Duration,
+}
+
+impl Default for LockOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(10)),
+            poll_interval: Duration::from_millis(10),
+        }
+    }
+}
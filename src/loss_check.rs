@@ -0,0 +1,674 @@
+use crate::errors::lossy_value_error::LossyValueError;
+use crate::format::Format;
+use crate::lossy_value_kind::LossyValueKind;
+use serde::ser::{Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
+use serde::{Serialize, Serializer};
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+
+/// The state shared by the [`LossCheck`] wrappers of a single transcoding
+///
+/// The serializers report every error as a message, so the context keeps the origin of the last failure (see [`LossCheckFailure`]).
+#[derive(Debug)]
+pub struct LossCheckContext {
+    pub from: Format,
+    pub to: Format,
+    path: RefCell<Vec<PathSegment>>,
+    is_capturing_key: Cell<bool>,
+    key: RefCell<Option<String>>,
+    failure: RefCell<Option<LossCheckFailure>>,
+    /// Incremented on every recorded failure (an error is recorded only where it originates, not on its way up)
+    failures_count: Cell<usize>,
+}
+
+/// The origin of the last error of a transcoding
+#[derive(Debug)]
+pub enum LossCheckFailure {
+    /// The deserializer of the input failed
+    Input,
+    /// The serializer of the output failed
+    Output,
+    /// The output format can't represent a value
+    Lossy(LossyValueError),
+}
+
+#[derive(Clone, Debug)]
+enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+impl LossCheckContext {
+    pub fn new(from: Format, to: Format) -> Self {
+        Self {
+            from,
+            to,
+            path: RefCell::default(),
+            is_capturing_key: Cell::default(),
+            key: RefCell::default(),
+            failure: RefCell::default(),
+            failures_count: Cell::default(),
+        }
+    }
+
+    pub fn take_failure(&self) -> Option<LossCheckFailure> {
+        self.failure.take()
+    }
+
+    /// Overwrites the previous failure, because some serializers recover from errors (e.g. the TOML serializer probes the values to choose the layout of the tables)
+    fn record(&self, failure: LossCheckFailure) {
+        *self.failure.borrow_mut() = Some(failure);
+        self.failures_count
+            .set(self.failures_count.get().wrapping_add(1));
+    }
+
+    /// Records the error of the call unless it was already recorded by a nested value
+    fn record_unrecorded<T, E>(&self, failure: LossCheckFailure, call: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let failures_count = self.failures_count.get();
+        let result = call();
+        if result.is_err() && self.failures_count.get() == failures_count {
+            self.record(failure);
+        }
+        result
+    }
+
+    pub fn lossy_value_error(&self, kind: LossyValueKind) -> LossyValueError {
+        LossyValueError {
+            kind,
+            path: self.path_string(),
+            from: self.from,
+            to: self.to,
+        }
+    }
+
+    fn path_string(&self) -> String {
+        let mut output = String::new();
+        for segment in self.path.borrow().iter() {
+            match segment {
+                PathSegment::Index(index) => output.push_str(&format!("[{index}]")),
+                PathSegment::Key(key) if output.is_empty() => output.push_str(key),
+                PathSegment::Key(key) => output.push_str(&format!(".{key}")),
+            }
+        }
+        output
+    }
+
+    #[allow(unreachable_code, unused_variables)]
+    fn lossy<E: Error>(&self, kind: LossyValueKind) -> E {
+        let error = self.lossy_value_error(kind);
+        let message = error.to_string();
+        self.record(LossCheckFailure::Lossy(error));
+        E::custom(message)
+    }
+
+    fn check_null<E: Error>(&self) -> Result<(), E> {
+        if has_null(self.to) { Ok(()) } else { Err(self.lossy(LossyValueKind::Null)) }
+    }
+
+    fn check_sequence<E: Error>(&self) -> Result<(), E> {
+        if has_sequences(self.to) { Ok(()) } else { Err(self.lossy(LossyValueKind::Sequence)) }
+    }
+
+    fn check_float<E: Error>(&self, is_finite: bool) -> Result<(), E> {
+        if is_finite || has_non_finite_numbers(self.to) {
+            Ok(())
+        } else {
+            Err(self.lossy(LossyValueKind::NonFiniteNumber))
+        }
+    }
+
+    fn capture_key(&self, key: impl Display) {
+        if self.is_capturing_key.get() {
+            *self.key.borrow_mut() = Some(key.to_string());
+        }
+    }
+
+    fn output<T, E>(&self, call: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        self.record_unrecorded(LossCheckFailure::Output, call)
+    }
+
+    fn compound<C, E>(&self, call: impl FnOnce() -> Result<C, E>) -> Result<LossCheckCompound<'_, C>, E> {
+        self.output(call).map(|inner| LossCheckCompound {
+            inner,
+            context: self,
+            index: 0,
+            key: None,
+        })
+    }
+}
+
+/// Whether the format can represent `null` (these matches have no catch-all arm, so that a new format has to opt in)
+fn has_null(format: Format) -> bool {
+    match format {
+        #[cfg(feature = "serde_json")]
+        Format::Json => true,
+        #[cfg(feature = "serde-jsonlines")]
+        Format::Jsonl => true,
+        #[cfg(feature = "json5")]
+        Format::Jsonc | Format::Json5 => true,
+        #[cfg(feature = "serde_yaml")]
+        Format::Yaml => true,
+        #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
+        Format::Xml => true,
+        #[cfg(feature = "toml")]
+        Format::Toml => false,
+        #[cfg(feature = "csv")]
+        Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => true,
+        #[cfg(feature = "ciborium")]
+        Format::Cbor => true,
+        #[cfg(feature = "rmp-serde")]
+        Format::MessagePack => true,
+        #[cfg(feature = "bincode")]
+        Format::Bincode => true,
+        #[cfg(feature = "postcard")]
+        Format::Postcard => true,
+        #[cfg(feature = "ron")]
+        Format::Ron => true,
+        #[cfg(feature = "rust-ini")]
+        Format::Ini => false,
+        #[cfg(feature = "properties")]
+        Format::Properties => false,
+    }
+}
+
+/// Whether the format can represent sequences (INI and properties write them as maps with indexed keys)
+fn has_sequences(format: Format) -> bool {
+    match format {
+        #[cfg(feature = "serde_json")]
+        Format::Json => true,
+        #[cfg(feature = "serde-jsonlines")]
+        Format::Jsonl => true,
+        #[cfg(feature = "json5")]
+        Format::Jsonc | Format::Json5 => true,
+        #[cfg(feature = "serde_yaml")]
+        Format::Yaml => true,
+        #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
+        Format::Xml => true,
+        #[cfg(feature = "toml")]
+        Format::Toml => true,
+        #[cfg(feature = "csv")]
+        Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => true,
+        #[cfg(feature = "ciborium")]
+        Format::Cbor => true,
+        #[cfg(feature = "rmp-serde")]
+        Format::MessagePack => true,
+        #[cfg(feature = "bincode")]
+        Format::Bincode => true,
+        #[cfg(feature = "postcard")]
+        Format::Postcard => true,
+        #[cfg(feature = "ron")]
+        Format::Ron => true,
+        #[cfg(feature = "rust-ini")]
+        Format::Ini => false,
+        #[cfg(feature = "properties")]
+        Format::Properties => false,
+    }
+}
+
+/// Whether the format can represent NaN and infinity
+fn has_non_finite_numbers(format: Format) -> bool {
+    match format {
+        #[cfg(feature = "serde_json")]
+        Format::Json => false,
+        #[cfg(feature = "serde-jsonlines")]
+        Format::Jsonl => false,
+        #[cfg(feature = "json5")]
+        Format::Jsonc | Format::Json5 => false,
+        #[cfg(feature = "serde_yaml")]
+        Format::Yaml => true,
+        #[cfg(any(feature = "serde-xml-rs", feature = "quick-xml"))]
+        Format::Xml => true,
+        #[cfg(feature = "toml")]
+        Format::Toml => true,
+        #[cfg(feature = "csv")]
+        Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => true,
+        #[cfg(feature = "ciborium")]
+        Format::Cbor => true,
+        #[cfg(feature = "rmp-serde")]
+        Format::MessagePack => true,
+        #[cfg(feature = "bincode")]
+        Format::Bincode => true,
+        #[cfg(feature = "postcard")]
+        Format::Postcard => true,
+        #[cfg(feature = "ron")]
+        Format::Ron => true,
+        #[cfg(feature = "rust-ini")]
+        Format::Ini => true,
+        #[cfg(feature = "properties")]
+        Format::Properties => true,
+    }
+}
+
+/// Serializes the value, rejecting the values that the output format of the context can't represent (see [`LossyValueKind`])
+pub struct LossCheck<'a, T> {
+    value: T,
+    context: &'a LossCheckContext,
+    segment: Option<PathSegment>,
+}
+
+impl<'a, T> LossCheck<'a, T> {
+    pub fn new(value: T, context: &'a LossCheckContext) -> Self {
+        Self {
+            value,
+            context,
+            segment: None,
+        }
+    }
+
+    /// The value is the item at the index of a sequence (e.g. of a many-item input)
+    pub fn at_index(value: T, context: &'a LossCheckContext, index: usize) -> Self {
+        Self {
+            value,
+            context,
+            segment: Some(PathSegment::Index(index)),
+        }
+    }
+
+    fn at_key(value: T, context: &'a LossCheckContext, key: String) -> Self {
+        Self {
+            value,
+            context,
+            segment: Some(PathSegment::Key(key)),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for LossCheck<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let context = self.context;
+        if let Some(segment) = &self.segment {
+            context.path.borrow_mut().push(segment.clone());
+        }
+        // The errors of the output are recorded by the serializer, so an unrecorded error comes from the value itself (e.g. from the deserializer of a transcoder)
+        let result = context.record_unrecorded(LossCheckFailure::Input, || {
+            self.value.serialize(LossCheckSerializer {
+                inner: serializer,
+                context,
+            })
+        });
+        if self.segment.is_some() {
+            context.path.borrow_mut().pop();
+        }
+        result
+    }
+}
+
+pub struct LossCheckSerializer<'a, S> {
+    inner: S,
+    context: &'a LossCheckContext,
+}
+
+impl<'a, S: Serializer> Serializer for LossCheckSerializer<'a, S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = LossCheckCompound<'a, S::SerializeSeq>;
+    type SerializeTuple = LossCheckCompound<'a, S::SerializeTuple>;
+    type SerializeTupleStruct = LossCheckCompound<'a, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = LossCheckCompound<'a, S::SerializeTupleVariant>;
+    type SerializeMap = LossCheckCompound<'a, S::SerializeMap>;
+    type SerializeStruct = LossCheckCompound<'a, S::SerializeStruct>;
+    type SerializeStructVariant = LossCheckCompound<'a, S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_i8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_i16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_i32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_i64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_i128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_u8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_u16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_u32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_u64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_u128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.context.check_float(v.is_finite())?;
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_f32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.context.check_float(v.is_finite())?;
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_f64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(v);
+        self.context.output(|| self.inner.serialize_str(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.context.check_sequence()?;
+        self.context.output(|| self.inner.serialize_bytes(v))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.context.check_null()?;
+        self.context.output(|| self.inner.serialize_none())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| {
+            self.inner
+                .serialize_some(&LossCheck::new(value, self.context))
+        })
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.context.check_null()?;
+        self.context.output(|| self.inner.serialize_unit())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.context.check_null()?;
+        self.context
+            .output(|| self.inner.serialize_unit_struct(name))
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.context.capture_key(variant);
+        self.context.output(|| {
+            self.inner
+                .serialize_unit_variant(name, variant_index, variant)
+        })
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| {
+            self.inner
+                .serialize_newtype_struct(name, &LossCheck::new(value, self.context))
+        })
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, name: &'static str, variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = LossCheck::at_key(value, self.context, variant.to_string());
+        self.context.output(|| {
+            self.inner
+                .serialize_newtype_variant(name, variant_index, variant, &value)
+        })
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.context.check_sequence()?;
+        self.context.compound(|| self.inner.serialize_seq(len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.context.check_sequence()?;
+        self.context.compound(|| self.inner.serialize_tuple(len))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.context.check_sequence()?;
+        self.context
+            .compound(|| self.inner.serialize_tuple_struct(name, len))
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.context.check_sequence()?;
+        self.context.compound(|| {
+            self.inner
+                .serialize_tuple_variant(name, variant_index, variant, len)
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.context.compound(|| self.inner.serialize_map(len))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.context
+            .compound(|| self.inner.serialize_struct(name, len))
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.context.compound(|| {
+            self.inner
+                .serialize_struct_variant(name, variant_index, variant, len)
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+pub struct LossCheckCompound<'a, C> {
+    inner: C,
+    context: &'a LossCheckContext,
+    /// The index of the next element
+    index: usize,
+    /// The last serialized map key (for the path of the value)
+    key: Option<String>,
+}
+
+impl<'a, C> LossCheckCompound<'a, C> {
+    fn next_element<'v, T: ?Sized>(&mut self, value: &'v T) -> LossCheck<'a, &'v T> {
+        let index = self.index;
+        self.index = self.index.saturating_add(1);
+        LossCheck::at_index(value, self.context, index)
+    }
+
+    fn field<'v, T: ?Sized>(&self, key: &str, value: &'v T) -> LossCheck<'a, &'v T> {
+        LossCheck::at_key(value, self.context, key.to_string())
+    }
+}
+
+impl<C: SerializeSeq> SerializeSeq for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.next_element(value);
+        self.context.output(|| self.inner.serialize_element(&value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeTuple> SerializeTuple for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.next_element(value);
+        self.context.output(|| self.inner.serialize_element(&value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeTupleStruct> SerializeTupleStruct for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.next_element(value);
+        self.context.output(|| self.inner.serialize_field(&value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeTupleVariant> SerializeTupleVariant for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let value = self.next_element(value);
+        self.context.output(|| self.inner.serialize_field(&value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeMap> SerializeMap for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let context = self.context;
+        context.is_capturing_key.set(true);
+        let result = context.output(|| self.inner.serialize_key(&LossCheck::new(key, context)));
+        context.is_capturing_key.set(false);
+        self.key = context.key.take();
+        result
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().unwrap_or_else(|| "?".to_string());
+        let value = self.field(&key, value);
+        self.context.output(|| self.inner.serialize_value(&value))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeStruct> SerializeStruct for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        let value = self.field(key, value);
+        self.context
+            .output(|| self.inner.serialize_field(key, &value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.context.output(|| self.inner.skip_field(key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+impl<C: SerializeStructVariant> SerializeStructVariant for LossCheckCompound<'_, C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        let value = self.field(key, value);
+        self.context
+            .output(|| self.inner.serialize_field(key, &value))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.context.output(|| self.inner.skip_field(key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.context.output(|| self.inner.end())
+    }
+}
+
+#[cfg(all(test, feature = "serde_yaml", any(feature = "json5", all(feature = "rust-ini", feature = "properties"))))]
+mod tests {
+    use crate::errors::transcode_error::TranscodeError;
+    use crate::format::Format;
+    use crate::lossy_value_kind::LossyValueKind;
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn must_reject_non_finite_number_in_json5_output() {
+        let mut output = Vec::new();
+        let error = Format::transcode(Format::Yaml, Format::Json5, "x: .nan\n".as_bytes(), &mut output).unwrap_err();
+        let TranscodeError::Lossy(error) = error else {
+            panic!("expected a lossy value error, found {error:?}");
+        };
+        assert_eq!(error.kind, LossyValueKind::NonFiniteNumber);
+        assert_eq!(error.path, "x");
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn must_reject_infinity_in_jsonc_output() {
+        let mut output = Vec::new();
+        let error = Format::transcode(Format::Yaml, Format::Jsonc, "ports: [1, .inf]\n".as_bytes(), &mut output).unwrap_err();
+        let TranscodeError::Lossy(error) = error else {
+            panic!("expected a lossy value error, found {error:?}");
+        };
+        assert_eq!(error.kind, LossyValueKind::NonFiniteNumber);
+        assert_eq!(error.path, "ports[1]");
+    }
+
+    #[cfg(all(feature = "rust-ini", feature = "properties"))]
+    fn lossy_value_kind(input: &str, to: Format) -> LossyValueKind {
+        let mut output = Vec::new();
+        match Format::transcode(Format::Yaml, to, input.as_bytes(), &mut output) {
+            Err(TranscodeError::Lossy(error)) => error.kind,
+            result => panic!("expected a lossy value error, found {result:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "rust-ini", feature = "properties"))]
+    #[test]
+    fn must_reject_null_and_sequence_in_key_value_output() {
+        for to in [Format::Ini, Format::Properties] {
+            assert_eq!(lossy_value_kind("x: null\n", to), LossyValueKind::Null);
+            assert_eq!(lossy_value_kind("server:\n  tags: [a, b]\n", to), LossyValueKind::Sequence);
+            assert_eq!(lossy_value_kind("[1, 2]\n", to), LossyValueKind::Sequence);
+            let mut output = Vec::new();
+            Format::transcode(Format::Yaml, to, "server:\n  port: 80\n".as_bytes(), &mut output).unwrap();
+        }
+    }
+}
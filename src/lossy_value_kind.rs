@@ -0,0 +1,22 @@
+use derive_more::Display;
+
+/// A value that the target format can't represent as is (see [`LossyValueError`](crate::errors::lossy_value_error::LossyValueError))
+#[derive(Display, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum LossyValueKind {
+    /// `null` in a format without nulls (TOML would drop the entry, INI and properties would write an empty string)
+    #[display("null")]
+    Null,
+    /// NaN or infinity in a format without them (JSON would write `null`)
+    #[display("non-finite number")]
+    NonFiniteNumber,
+    /// A sequence or bytes in a format that writes them as a map with indexed keys (INI and properties read them back as a map)
+    #[display("sequence")]
+    Sequence,
+    /// A sequence, a map or bytes in a field of a flat format (CSV)
+    #[display("nested value")]
+    NestedValue,
+    /// A key of a later record that is not in the header taken from the first record (CSV)
+    #[display("column that is not in the header")]
+    UnknownColumn,
+}
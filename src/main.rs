@@ -0,0 +1,13 @@
+use clap::Parser;
+use save_load::cli::Cli;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match Cli::parse().run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error.render());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::format_options::FormatOptions;
+
+/// Options for the many-item operations (e.g. [`Format::load_many_with`](crate::format::Format::load_many_with))
+///
+/// Each field applies only to the formats mentioned in its name (except `format`, which applies to serialization in all formats, and whose `csv` applies to deserialization too).
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct ManyOptions {
+    pub format: FormatOptions,
+    /// The element that wraps all items
+    pub xml_root_tag: String,
+    /// The element that contains a single item
+    pub xml_item_tag: String,
+    /// The key of the array of tables that contains the items (e.g. `[[item]]`)
+    pub toml_key: String,
+}
+
+impl Default for ManyOptions {
+    fn default() -> Self {
+        Self {
+            format: FormatOptions::default(),
+            xml_root_tag: "items".to_string(),
+            xml_item_tag: "item".to_string(),
+            toml_key: "item".to_string(),
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use crate::append_many_options::AppendManyOptions;
+use crate::compression::Compression;
+use crate::errors::append_many_error::AppendManyError;
+use crate::errors::append_many_error_kind::AppendManyErrorKind;
+use crate::errors::compressed_append_error::CompressedAppendError;
+use crate::errors::serialize_one_error::SerializeOneError;
+use crate::errors::unsupported_format_error::UnsupportedFormatError;
+use crate::flush_policy::FlushPolicy;
+use crate::format::Format;
+use crate::sync_policy::SyncPolicy;
+use serde::Serialize;
+use std::fmt::{Debug, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// Appends the items to a file one by one, keeping the file open (see [`Format::append_many`])
+///
+/// Dropping the writer finishes it too, but ignores the errors, so call [`ManyWriter::finish`] to get them.
+pub struct ManyWriter<T: Serialize> {
+    path: PathBuf,
+    format: Format,
+    backend: ManyWriterBackend,
+    flush_policy: FlushPolicy,
+    sync_policy: SyncPolicy,
+    /// The number of items pushed since the last flush
+    unflushed_count: usize,
+    is_finished: bool,
+    marker: PhantomData<fn(&T)>,
+}
+
+enum ManyWriterBackend {
+    #[cfg(feature = "serde-jsonlines")]
+    Jsonl(serde_jsonlines::JsonLinesWriter<std::io::BufWriter<File>>),
+    /// Boxed because the CSV writer is much larger than the other writers
+    #[cfg(feature = "csv")]
+    Csv(Box<csv::Writer<File>>),
+}
+
+impl<T: Serialize> ManyWriter<T> {
+    /// Opens the file in append mode, creating it if it doesn't exist (the CSV headers are written only to an empty file)
+    ///
+    /// The compressed files are refused (see [`Compression::from_path`]), because the items would be appended to them uncompressed.
+    ///
+    /// If the last line of the file is not terminated (e.g. it was written by a process that crashed or by an editor), a line terminator (the one of the CSV dialect for the CSV-like formats) is written first, so that the first item doesn't continue that line.
+    #[allow(unreachable_patterns, unused_variables)]
+    pub fn open(format: Format, path: impl AsRef<Path>, options: &AppendManyOptions) -> Result<Self, AppendManyError> {
+        let path = path.as_ref();
+        let to_error = |source: AppendManyErrorKind| AppendManyError::new(path, format, source);
+        if let Some(compression) = Compression::from_path(path) {
+            return Err(to_error(
+                CompressedAppendError {
+                    compression,
+                }
+                .into(),
+            ));
+        }
+        let open = |terminator: &[u8]| {
+            OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create(true)
+                .open(path)
+                .and_then(|mut file| terminate_last_line(&mut file, terminator).map(|()| file))
+                .map_err(|error| to_error(error.into()))
+        };
+        let backend = match format {
+            #[cfg(feature = "serde-jsonlines")]
+            Format::Jsonl => ManyWriterBackend::Jsonl(serde_jsonlines::JsonLinesWriter::new(std::io::BufWriter::new(open(b"\n")?))),
+            #[cfg(feature = "csv")]
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                let csv_options = options.csv.unwrap_or_else(|| format.csv_options());
+                let file = open(&csv_options.terminator.to_bytes())?;
+                let is_empty = file
+                    .metadata()
+                    .map_err(|error| to_error(error.into()))?
+                    .len()
+                    == 0;
+                let writer = csv_options
+                    .writer_builder()
+                    .has_headers(csv_options.has_headers && is_empty)
+                    .from_writer(file);
+                ManyWriterBackend::Csv(Box::new(writer))
+            }
+            _ => {
+                return Err(to_error(
+                    UnsupportedFormatError {
+                        format,
+                    }
+                    .into(),
+                ));
+            }
+        };
+        Ok(Self {
+            path: path.into(),
+            format,
+            backend,
+            flush_policy: options.flush,
+            sync_policy: options.sync,
+            unflushed_count: 0,
+            is_finished: false,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn push(&mut self, item: &T) -> Result<(), AppendManyError> {
+        let result = match &mut self.backend {
+            #[cfg(feature = "serde-jsonlines")]
+            ManyWriterBackend::Jsonl(writer) => writer
+                .write(item)
+                .map_err(SerializeOneError::SerdeJsonlines),
+            #[cfg(feature = "csv")]
+            ManyWriterBackend::Csv(writer) => writer.serialize(item).map_err(SerializeOneError::Csv),
+        };
+        result.map_err(|source| self.error(source))?;
+        self.unflushed_count = self.unflushed_count.saturating_add(1);
+        if self.flush_policy.is_due(self.unflushed_count) {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the buffer to the file (and fsyncs the file if the policy is [`SyncPolicy::OnFlush`])
+    pub fn flush(&mut self) -> Result<(), AppendManyError> {
+        self.flush_backend()?;
+        if self.sync_policy == SyncPolicy::OnFlush {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the buffer to the file (and fsyncs the file unless the policy is [`SyncPolicy::Never`])
+    pub fn finish(mut self) -> Result<(), AppendManyError> {
+        self.is_finished = true;
+        self.finish_mut()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    fn finish_mut(&mut self) -> Result<(), AppendManyError> {
+        self.flush_backend()?;
+        if self.sync_policy != SyncPolicy::Never {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    fn flush_backend(&mut self) -> Result<(), AppendManyError> {
+        let result = match &mut self.backend {
+            #[cfg(feature = "serde-jsonlines")]
+            ManyWriterBackend::Jsonl(writer) => writer.flush(),
+            #[cfg(feature = "csv")]
+            ManyWriterBackend::Csv(writer) => writer.flush(),
+        };
+        result.map_err(|source| self.error(source))?;
+        self.unflushed_count = 0;
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), AppendManyError> {
+        self.file().sync_data().map_err(|source| self.error(source))
+    }
+
+    fn file(&self) -> &File {
+        match &self.backend {
+            #[cfg(feature = "serde-jsonlines")]
+            ManyWriterBackend::Jsonl(writer) => writer.get_ref().get_ref(),
+            #[cfg(feature = "csv")]
+            ManyWriterBackend::Csv(writer) => writer.get_ref(),
+        }
+    }
+
+    fn error(&self, source: impl Into<AppendManyErrorKind>) -> AppendManyError {
+        AppendManyError::new(&self.path, self.format, source)
+    }
+}
+
+/// Writes the terminator if the file is not empty and doesn't end with the last byte of the terminator (the writes of a file in append mode go to the end regardless of the position)
+fn terminate_last_line(file: &mut File, terminator: &[u8]) -> io::Result<()> {
+    let len = file.metadata()?.len();
+    let Some(last_byte_position) = len.checked_sub(1) else {
+        return Ok(());
+    };
+    file.seek(SeekFrom::Start(last_byte_position))?;
+    let mut last_byte = [0u8];
+    file.read_exact(&mut last_byte)?;
+    if terminator.last() != Some(&last_byte[0]) {
+        file.write_all(terminator)?;
+    }
+    Ok(())
+}
+
+impl<T: Serialize> Debug for ManyWriter<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManyWriter")
+            .field("path", &self.path)
+            .field("format", &self.format)
+            .field("flush_policy", &self.flush_policy)
+            .field("sync_policy", &self.sync_policy)
+            .field("unflushed_count", &self.unflushed_count)
+            .field("is_finished", &self.is_finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Serialize> Drop for ManyWriter<T> {
+    fn drop(&mut self) {
+        if !self.is_finished {
+            let _ = self.finish_mut();
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "serde-jsonlines", feature = "csv")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "csv")]
+    #[derive(Serialize)]
+    struct Row {
+        id: u32,
+        name: &'static str,
+    }
+
+    #[cfg(feature = "csv")]
+    fn append_rows(path: &Path, rows: &[Row], options: &AppendManyOptions) {
+        let mut writer = ManyWriter::open(Format::Csv, path, options).unwrap();
+        for row in rows {
+            writer.push(row).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[cfg(all(feature = "serde-jsonlines", feature = "flate2"))]
+    #[test]
+    fn must_refuse_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl.gz");
+        let error = Format::Jsonl
+            .append_many::<u32>(&path)
+            .map(|_| ())
+            .unwrap_err();
+        assert!(matches!(error.source, AppendManyErrorKind::Compressed(_)), "{error}");
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "serde-jsonlines")]
+    #[test]
+    fn must_terminate_last_line_before_appending() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        std::fs::write(&path, "1\n2").unwrap();
+        let mut writer = ManyWriter::open(Format::Jsonl, &path, &AppendManyOptions::default()).unwrap();
+        writer.push(&3).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        let mut writer = ManyWriter::open(Format::Jsonl, &path, &AppendManyOptions::default()).unwrap();
+        writer.push(&4).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n4\n");
+    }
+
+    #[cfg(feature = "serde-jsonlines")]
+    #[test]
+    fn must_append_to_new_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let mut writer = ManyWriter::open(Format::Jsonl, &path, &AppendManyOptions::default()).unwrap();
+        writer.push(&1).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n");
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_terminate_last_line_with_dialect_terminator() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "id,name\r\n1,a").unwrap();
+        let options = AppendManyOptions {
+            csv: Some(crate::csv_options::CsvOptions {
+                terminator: crate::csv_terminator::CsvTerminator::Crlf,
+                ..Format::Csv.csv_options()
+            }),
+            ..AppendManyOptions::default()
+        };
+        append_rows(
+            &path,
+            &[Row {
+                id: 2,
+                name: "b",
+            }],
+            &options,
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id,name\r\n1,a\r\n2,b\r\n");
+        append_rows(
+            &path,
+            &[Row {
+                id: 3,
+                name: "c",
+            }],
+            &options,
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id,name\r\n1,a\r\n2,b\r\n3,c\r\n");
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn must_write_headers_only_to_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+        std::fs::write(&path, "").unwrap();
+        let options = AppendManyOptions::default();
+        append_rows(
+            &path,
+            &[Row {
+                id: 1,
+                name: "a",
+            }],
+            &options,
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id,name\n1,a\n");
+        append_rows(
+            &path,
+            &[
+                Row {
+                    id: 2,
+                    name: "b",
+                },
+                Row {
+                    id: 3,
+                    name: "c",
+                },
+            ],
+            &options,
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id,name\n1,a\n2,b\n3,c\n");
+        let new_path = dir.path().join("new.csv");
+        append_rows(
+            &new_path,
+            &[Row {
+                id: 4,
+                name: "d",
+            }],
+            &options,
+        );
+        assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "id,name\n4,d\n");
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, VariantArray};
+
+/// The operation that failed (used in the errors of the `load_*` and `save_*` methods)
+#[derive(Serialize, Deserialize, Display, VariantArray, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[strum(serialize_all = "lowercase")]
+pub enum Operation {
+    Load,
+    Save,
+    Serialize,
+    Deserialize,
+}
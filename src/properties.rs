@@ -0,0 +1,211 @@
+//! Java `.properties` files (the syntax of `java.util.Properties`) as [`StringTree`]s: the dotted keys are nested (e.g. `server.port=80`)
+
+use crate::errors::invalid_escape_error::InvalidEscapeError;
+use crate::string_tree::StringTree;
+use std::io::Write;
+
+/// The whitespace of the properties syntax (the other Unicode whitespace is a part of the keys and the values)
+const WHITESPACE: [char; 3] = [' ', '\t', '\x0c'];
+
+/// Parses the properties text
+///
+/// The lines starting with `#` or `!` are comments. A line ending with an odd number of backslashes continues on the next line (without its leading whitespace). The key ends at the first unescaped `=`, `:` or whitespace. The escapes are `\t`, `\n`, `\r`, `\f` and `\uXXXX`, any other escaped character stands for itself. The last value of a duplicate key wins.
+pub fn parse(input: &str) -> Result<StringTree, InvalidEscapeError> {
+    let mut tree = StringTree::default();
+    let mut lines = natural_lines(input).enumerate();
+    while let Some((index, line)) = lines.next() {
+        let mut line = line.trim_start_matches(WHITESPACE);
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+        let mut logical_line = String::new();
+        while is_continued(line) {
+            logical_line.push_str(&line[..line.len().saturating_sub(1)]);
+            match lines.next() {
+                Some((_, next_line)) => line = next_line.trim_start_matches(WHITESPACE),
+                None => {
+                    line = "";
+                    break;
+                }
+            }
+        }
+        logical_line.push_str(line);
+        let line_number = index.saturating_add(1);
+        let (key, value) = split_entry(&logical_line);
+        tree.insert(&unescape(key, line_number)?, unescape(value, line_number)?, Some(line_number));
+    }
+    Ok(tree)
+}
+
+/// Writes a `key=value` line per value in the order of the tree (the keys are the dotted paths)
+///
+/// The non-ASCII characters are written as `\uXXXX` escapes, so the output is valid in any encoding that Java reads the properties files in (ISO-8859-1 before Java 9, UTF-8 since).
+pub fn write(writer: &mut impl Write, tree: StringTree) -> std::io::Result<()> {
+    for (key, value) in tree.into_entries() {
+        writeln!(writer, "{}={}", escape(&key, true), escape(&value, false))?;
+    }
+    Ok(())
+}
+
+/// Splits on `\n`, `\r\n` and `\r`
+fn natural_lines(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split('\n')
+        .flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+}
+
+fn is_continued(line: &str) -> bool {
+    let backslashes = line.len().saturating_sub(line.trim_end_matches('\\').len());
+    backslashes % 2 == 1
+}
+
+/// Returns the key and the value, both still escaped
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut is_escaped = false;
+    let key_end = line
+        .char_indices()
+        .find(|&(_, char)| {
+            let is_end = !is_escaped && (char == '=' || char == ':' || WHITESPACE.contains(&char));
+            is_escaped = !is_escaped && char == '\\';
+            is_end
+        })
+        .map_or(line.len(), |(index, _)| index);
+    let (key, rest) = line.split_at(key_end);
+    let rest = rest.trim_start_matches(WHITESPACE);
+    let rest = rest
+        .strip_prefix(['=', ':'])
+        .unwrap_or(rest)
+        .trim_start_matches(WHITESPACE);
+    (key, rest)
+}
+
+/// Decodes the escapes (the `\uXXXX` escapes are UTF-16 code units, so a character outside the BMP is a surrogate pair)
+fn unescape(input: &str, line: usize) -> Result<String, InvalidEscapeError> {
+    let error = InvalidEscapeError {
+        line,
+    };
+    let mut units = Vec::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(char) = chars.next() {
+        let char = match char {
+            '\\' => match chars.next() {
+                Some('u') => {
+                    let digits = chars.by_ref().take(4).collect::<String>();
+                    if digits.len() != 4 || !digits.chars().all(|char| char.is_ascii_hexdigit()) {
+                        return Err(error);
+                    }
+                    units.push(u16::from_str_radix(&digits, 16).map_err(|_| error)?);
+                    continue;
+                }
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('f') => '\x0c',
+                Some(char) => char,
+                // A backslash at the end of the input (the last line is continued) is dropped
+                None => break,
+            },
+            char => char,
+        };
+        units.extend(char.encode_utf16(&mut [0; 2]).iter());
+    }
+    String::from_utf16(&units).map_err(|_| error)
+}
+
+/// Escapes the backslash, the whitespace, the comment and separator characters and the non-printable or non-ASCII characters (`is_key` also escapes the spaces after the first character, which would end the key)
+fn escape(input: &str, is_key: bool) -> String {
+    let mut output = String::with_capacity(input.len());
+    for (index, char) in input.char_indices() {
+        match char {
+            ' ' if is_key || index == 0 => output.push_str("\\ "),
+            '\\' | '=' | ':' | '#' | '!' => {
+                output.push('\\');
+                output.push(char);
+            }
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\x0c' => output.push_str("\\f"),
+            ' '..='~' => output.push(char),
+            _ => {
+                for unit in char.encode_utf16(&mut [0; 2]).iter() {
+                    output.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(tree: &'a StringTree, path: &str) -> Option<&'a str> {
+        path.split('.')
+            .try_fold(tree, |node, key| {
+                node.children
+                    .iter()
+                    .find(|(child_key, _)| child_key == key)
+                    .map(|(_, child)| child)
+            })
+            .and_then(|node| node.value.as_deref())
+    }
+
+    #[test]
+    fn must_parse_separators_and_comments() {
+        let tree = parse("# comment\n! comment\nname = a\nport:80\nhost localhost\n  server.tls.cert=x.pem\nempty\n").unwrap();
+        assert_eq!(value(&tree, "name"), Some("a"));
+        assert_eq!(value(&tree, "port"), Some("80"));
+        assert_eq!(value(&tree, "host"), Some("localhost"));
+        assert_eq!(value(&tree, "server.tls.cert"), Some("x.pem"));
+        assert_eq!(value(&tree, "empty"), Some(""));
+        assert_eq!(tree.children.len(), 5);
+    }
+
+    #[test]
+    fn must_join_continuation_lines() {
+        let tree = parse("fruits = apple, \\\n    banana, \\\r\n\tcherry\npath = C:\\\\\nnext = 1\n").unwrap();
+        assert_eq!(value(&tree, "fruits"), Some("apple, banana, cherry"));
+        // An even number of backslashes is an escaped backslash, not a continuation
+        assert_eq!(value(&tree, "path"), Some("C:\\"));
+        assert_eq!(value(&tree, "next"), Some("1"));
+        let next = tree.children.iter().find(|(key, _)| key == "next").unwrap();
+        assert_eq!(next.1.line, Some(5));
+    }
+
+    #[test]
+    fn must_unescape_keys_and_values() {
+        let tree = parse("a\\=b\\ c = \\tx\\n\\u00e9\\uD83D\\uDE00\\q\n").unwrap();
+        assert_eq!(value(&tree, "a=b c"), Some("\tx\né😀q"));
+        assert_eq!(parse("a = \\u00\n").unwrap_err().line, 1);
+        assert_eq!(parse("a = 1\nb = \\uD83D\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn must_keep_last_duplicate_key() {
+        let tree = parse("a = 1\nb = 2\na = 3\n").unwrap();
+        assert_eq!(value(&tree, "a"), Some("3"));
+        assert_eq!(tree.children[0].1.line, Some(3));
+    }
+
+    #[test]
+    fn must_round_trip_special_characters() {
+        let mut tree = StringTree::default();
+        for (key, value) in [
+            ("key with spaces", " leading space"),
+            ("a:b=c#d!e", "é😀\\"),
+            ("lines", "one\ntwo\r\n"),
+        ] {
+            tree.insert(key, value.to_string(), None);
+        }
+        let mut output = Vec::new();
+        write(&mut output, tree.clone()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.is_ascii(), "{output}");
+        let parsed = parse(&output).unwrap();
+        for (key, node) in &tree.children {
+            assert_eq!(value(&parsed, key), node.value.as_deref(), "{output}");
+        }
+    }
+}
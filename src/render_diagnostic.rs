@@ -0,0 +1,57 @@
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme};
+
+/// Renders the diagnostic as plain text (without colors and without the cause chain, because the messages of this crate already include the messages of their sources)
+pub fn render_diagnostic(diagnostic: &dyn Diagnostic) -> String {
+    let mut output = String::new();
+    let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor()).without_cause_chain();
+    match handler.render_report(&mut output, diagnostic) {
+        Ok(()) => output,
+        Err(_) => diagnostic.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    use super::*;
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    use crate::errors::load_one_error::LoadOneError;
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    use crate::format::Format;
+
+    /// Returns the labelled text and the rendered report
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    fn render(format: Format, input: &str) -> (String, String) {
+        let source = format
+            .deserialize_one::<std::collections::BTreeMap<String, u16>>(input)
+            .unwrap_err();
+        let error = LoadOneError::from_input("config", format, source, input.as_bytes());
+        let label = error.labels().unwrap().next().unwrap();
+        let labelled = input[label.offset()..]
+            .chars()
+            .take(label.len().max(1))
+            .collect();
+        (labelled, render_diagnostic(&error))
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn must_label_json_error_after_non_ascii_text() {
+        let (labelled, report) = render(Format::Json, "{\n  \"größe\": 1, \"port\": \"x\"\n}");
+        assert_eq!(labelled, "\"");
+        assert!(report.contains("Could not deserialize config:2:25 as Json"), "{report}");
+        assert!(report.contains(" 2 │   \"größe\": 1, \"port\": \"x\"\n"), "{report}");
+        assert!(report.contains("   ·                         ─\n"), "{report}");
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    #[test]
+    fn must_label_yaml_error_after_non_ascii_text() {
+        let (labelled, report) = render(Format::Yaml, "# Einstellungen für den Server\nport: x\n");
+        assert_eq!(labelled, "x");
+        assert!(report.contains("Could not deserialize config:2:7 as Yaml"), "{report}");
+        assert!(report.contains(" 2 │ port: x\n"), "{report}");
+        assert!(report.contains("   ·       ─\n"), "{report}");
+        assert!(report.contains("help: check the value of `port`"), "{report}");
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, VariantArray};
+
+/// How [`Format::save_one_with`](crate::format::Format::save_one_with) writes the file to disk
+#[derive(Serialize, Deserialize, Display, VariantArray, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SaveMode {
+    /// Write to a sibling temp file, fsync it, rename it over the target, then fsync the parent directory.
+    ///
+    /// A crash or a serialization error never leaves a truncated file behind: the target contains either the old contents or the new contents.
+    #[default]
+    Atomic,
+    /// Truncate the target and write into it directly.
+    ///
+    /// Faster and keeps the inode (hard links, open handles), but a failure halfway through (including a serialization error) leaves a partially written file.
+    Truncate,
+}
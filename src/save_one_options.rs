@@ -0,0 +1,11 @@
+use crate::format_options::FormatOptions;
+use crate::save_mode::SaveMode;
+
+/// Options for [`Format::save_one_with`](crate::format::Format::save_one_with)
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct SaveOneOptions {
+    pub mode: SaveMode,
+    pub format: FormatOptions,
+    /// The compression level for the paths with a compression extension (`None` means the default level, see [`Compression::write_compressed`](crate::compression::Compression::write_compressed))
+    pub compression_level: Option<u32>,
+}
@@ -0,0 +1,24 @@
+/// The input that failed to load, kept for rendering diagnostics
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct SourceText {
+    pub name: String,
+    pub text: String,
+}
+
+impl SourceText {
+    pub fn new(name: impl Into<String>, bytes: &[u8]) -> Self {
+        Self {
+            name: name.into(),
+            text: String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+}
+
+impl miette::SourceCode for SourceText {
+    fn read_span<'a>(&'a self, span: &miette::SourceSpan, context_lines_before: usize, context_lines_after: usize) -> Result<Box<dyn miette::SpanContents<'a> + 'a>, miette::MietteError> {
+        let contents = self
+            .text
+            .read_span(span, context_lines_before, context_lines_after)?;
+        Ok(Box::new(miette::MietteSpanContents::new_named(self.name.clone(), contents.data(), *contents.span(), contents.line(), contents.column(), contents.line_count())))
+    }
+}
@@ -0,0 +1,12 @@
+use std::io;
+
+/// Runs `f` on the blocking thread pool of tokio
+///
+/// A panic in `f` is resumed on the calling task; a cancelled task (the runtime is shutting down) is returned as an [`io::Error`].
+pub async fn spawn_blocking<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> io::Result<R> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(output) => Ok(output),
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(error) => Err(io::Error::other(error)),
+    }
+}
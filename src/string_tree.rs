@@ -0,0 +1,125 @@
+/// The common model of the formats whose values are all strings (INI and Java properties): the sections and the dotted keys are nested nodes, the sequences are nodes with indexed keys (e.g. `servers.0.host`)
+///
+/// The keys themselves can't contain dots, because the dots are read back as nesting.
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct StringTree {
+    /// The value of the key itself (a key may have both a value and children, e.g. `log=info` next to `log.http=debug`)
+    pub value: Option<String>,
+    /// In the order of the input
+    pub children: Vec<(String, StringTree)>,
+    /// The one-based line of the key (or of the first key under it) in the input, `None` for the trees that were not parsed
+    pub line: Option<usize>,
+}
+
+impl StringTree {
+    pub fn leaf(value: impl Into<String>) -> Self {
+        Self {
+            value: Some(value.into()),
+            children: Vec::new(),
+            line: None,
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns the child, inserting an empty one if there is none
+    pub fn child_mut(&mut self, key: &str) -> &mut StringTree {
+        let index = match self
+            .children
+            .iter()
+            .position(|(child_key, _)| child_key == key)
+        {
+            Some(index) => index,
+            None => {
+                self.children.push((key.to_string(), StringTree::default()));
+                self.children.len().saturating_sub(1)
+            }
+        };
+        &mut self.children[index].1
+    }
+
+    /// Returns the descendant at the dotted path (e.g. `server.tls`), inserting the missing nodes
+    pub fn descendant_mut(&mut self, path: &str) -> &mut StringTree {
+        path.split('.').fold(self, |node, key| node.child_mut(key))
+    }
+
+    /// Sets the value at the dotted path, replacing the previous value (the last occurrence of a duplicate key wins)
+    ///
+    /// The `line` of the key replaces the previous one too, the nodes above the key keep their first line.
+    pub fn insert(&mut self, path: &str, value: String, line: Option<usize>) {
+        let node = path.split('.').fold(self, |node, key| {
+            let child = node.child_mut(key);
+            child.line = child.line.or(line);
+            child
+        });
+        node.value = Some(value);
+        node.line = line.or(node.line);
+    }
+
+    /// Returns the values with their dotted paths, each value before the values of its children
+    ///
+    /// The empty nodes (e.g. empty structs) are returned as empty values, so they are read back.
+    pub fn into_entries(self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        self.collect_entries(None, &mut entries);
+        entries
+    }
+
+    fn collect_entries(self, path: Option<&str>, entries: &mut Vec<(String, String)>) {
+        if let Some(path) = path {
+            match self.value {
+                Some(value) => entries.push((path.to_string(), value)),
+                None if self.is_leaf() => entries.push((path.to_string(), String::new())),
+                None => {}
+            }
+        }
+        for (key, child) in self.children {
+            let child_path = join_path(path, &key);
+            child.collect_entries(Some(&child_path), entries);
+        }
+    }
+}
+
+/// Joins the dotted path of the parent (`None` for the root) with the key
+pub fn join_path(parent: Option<&str>, key: &str) -> String {
+    match parent {
+        Some(parent) => format!("{parent}.{key}"),
+        None => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_keep_first_line_of_parents_and_last_line_of_keys() {
+        let mut tree = StringTree::default();
+        tree.insert("server.port", "80".to_string(), Some(2));
+        tree.insert("log", "info".to_string(), Some(3));
+        tree.insert("server.host", "a".to_string(), Some(4));
+        tree.insert("server.port", "81".to_string(), Some(5));
+        tree.insert("log.http", "debug".to_string(), Some(6));
+        let (_, server) = &tree.children[0];
+        assert_eq!(server.line, Some(2));
+        assert_eq!(server.children[0].1.line, Some(5));
+        assert_eq!(
+            tree.into_entries(),
+            vec![
+                ("server.port".to_string(), "81".to_string()),
+                ("server.host".to_string(), "a".to_string()),
+                ("log".to_string(), "info".to_string()),
+                ("log.http".to_string(), "debug".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn must_return_empty_nodes_as_empty_values() {
+        let mut tree = StringTree::default();
+        tree.descendant_mut("server.tls");
+        assert_eq!(tree.into_entries(), vec![("server.tls".to_string(), String::new())]);
+    }
+}
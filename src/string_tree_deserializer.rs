@@ -0,0 +1,350 @@
+use crate::errors::string_tree_error::StringTreeError;
+use crate::string_tree::StringTree;
+use serde::de::value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{Error, IntoDeserializer, Visitor};
+use std::str::FromStr;
+
+/// Parses the scalars on demand (all values are strings, so e.g. `port=80` can be deserialized into a `u16`)
+impl<'de> serde::Deserializer<'de> for StringTree {
+    type Error = StringTreeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            _ if !self.is_leaf() => self.deserialize_map(visitor),
+            Some(value) => visitor.visit_string(value),
+            None => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let line = self.line;
+        let value = self.into_scalar()?;
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => visitor.visit_bool(true),
+            "false" | "no" | "off" | "0" => visitor.visit_bool(false),
+            _ => Err(StringTreeError::custom(format!("invalid bool: {value:?} (expected true, false, yes, no, on, off, 1 or 0)")).with_line(line)),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i8(self.parse("i8")?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i16(self.parse("i16")?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i32(self.parse("i32")?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i64(self.parse("i64")?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i128(self.parse("i128")?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u8(self.parse("u8")?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u16(self.parse("u16")?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u32(self.parse("u32")?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u64(self.parse("u64")?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u128(self.parse("u128")?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f32(self.parse("f32")?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f64(self.parse("f64")?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_char(self.parse("char")?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.into_scalar()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.into_scalar()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_byte_buf(self.into_scalar()?.into_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_byte_buf(self.into_scalar()?.into_bytes())
+    }
+
+    /// An empty value is `None` (the missing keys are `None` too)
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.as_deref() {
+            Some("") | None if self.is_leaf() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Accepts the indexed keys (e.g. `tags.0=a` and `tags.1=b`) or a comma-separated value (e.g. `tags=a, b`)
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let line = self.line;
+        if self.is_leaf() {
+            let value = self.value.unwrap_or_default();
+            let items = value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(StringTree::leaf)
+                .collect::<Vec<_>>();
+            return visit_seq(items, visitor).map_err(|error| error.with_line(line));
+        }
+        let mut items = self
+            .children
+            .into_iter()
+            .map(|(key, child)| match key.parse::<usize>() {
+                Ok(index) => Ok((index, child)),
+                Err(_) => Err(StringTreeError::custom(format!("expected a sequence with indexed keys (e.g. `0`, `1`), found the key `{key}`")).with_line(child.line.or(line))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        items.sort_by_key(|(index, _)| *index);
+        visit_seq(items.into_iter().map(|(_, child)| child).collect(), visitor).map_err(|error| error.with_line(line))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    /// The errors of the values are located at their keys, the other errors (e.g. a missing field) at the first key of the map
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let line = self.line;
+        let mut map = MapDeserializer::new(into_entries(self.children));
+        let output = visitor
+            .visit_map(&mut map)
+            .map_err(|error| error.with_line(line))?;
+        map.end().map_err(|error| error.with_line(line))?;
+        Ok(output)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    /// Accepts a unit variant as a value (e.g. `mode=fast`) and the other variants as a single child (e.g. `mode.limited=10`)
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        let line = self.line;
+        if self.is_leaf() {
+            let variant: StringDeserializer<StringTreeError> = self.value.unwrap_or_default().into_deserializer();
+            return visitor
+                .visit_enum(variant)
+                .map_err(|error| error.with_line(line));
+        }
+        if self.children.len() != 1 {
+            return Err(StringTreeError::custom(format!("expected a single key with the name of the enum variant, found {} keys", self.children.len())).with_line(line));
+        }
+        visitor
+            .visit_enum(MapAccessDeserializer::new(MapDeserializer::new(into_entries(self.children))))
+            .map_err(|error| error.with_line(line))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.into_scalar()?)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+impl IntoDeserializer<'_, StringTreeError> for StringTree {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl StringTree {
+    fn into_scalar(self) -> Result<String, StringTreeError> {
+        match self.value {
+            Some(value) if self.is_leaf() => Ok(value),
+            None if self.is_leaf() => Ok(String::new()),
+            _ => Err(StringTreeError::custom("expected a value, found a section (or keys nested under this key)").with_line(self.line)),
+        }
+    }
+
+    fn parse<T: FromStr<Err: std::fmt::Display>>(self, type_name: &str) -> Result<T, StringTreeError> {
+        let line = self.line;
+        let value = self.into_scalar()?;
+        value
+            .trim()
+            .parse()
+            .map_err(|error| StringTreeError::custom(format!("invalid {type_name}: {value:?} ({error})")).with_line(line))
+    }
+}
+
+/// Returns the keys as leaves on the lines of their values (so e.g. an unknown field is located at its key)
+fn into_entries(children: Vec<(String, StringTree)>) -> impl Iterator<Item = (StringTree, StringTree)> {
+    children.into_iter().map(|(key, child)| {
+        let key = StringTree {
+            line: child.line,
+            ..StringTree::leaf(key)
+        };
+        (key, child)
+    })
+}
+
+fn visit_seq<'de, V: Visitor<'de>>(items: Vec<StringTree>, visitor: V) -> Result<V::Value, StringTreeError> {
+    let mut seq = SeqDeserializer::new(items.into_iter());
+    let output = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string_tree::StringTree;
+    use crate::string_tree_serializer::to_string_tree;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Mode {
+        Fast,
+        Limited(u32),
+        Custom { rate: u32 },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Server {
+        host: String,
+        port: u16,
+        tls: bool,
+        tags: Vec<String>,
+        weight: Option<f64>,
+        modes: Vec<Mode>,
+        labels: BTreeMap<String, String>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        name: String,
+        servers: Vec<Server>,
+    }
+
+    #[test]
+    fn must_round_trip_nested_values() {
+        let config = Config {
+            name: "app".to_string(),
+            servers: vec![Server {
+                host: "localhost".to_string(),
+                port: 80,
+                tls: true,
+                tags: vec!["a".to_string(), "b".to_string()],
+                weight: None,
+                modes: vec![
+                    Mode::Fast,
+                    Mode::Limited(10),
+                    Mode::Custom {
+                        rate: 5,
+                    },
+                ],
+                labels: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+            }],
+        };
+        let tree = to_string_tree(&config).unwrap();
+        let entries = tree.clone().into_entries();
+        assert!(entries.contains(&("servers.0.tags.1".to_string(), "b".to_string())), "{entries:?}");
+        assert!(entries.contains(&("servers.0.modes.1.Limited".to_string(), "10".to_string())), "{entries:?}");
+        assert!(!entries.iter().any(|(key, _)| key.ends_with("weight")), "{entries:?}");
+        assert_eq!(Config::deserialize(tree).unwrap(), config);
+    }
+
+    #[test]
+    fn must_parse_scalars_on_demand() {
+        let mut tree = StringTree::default();
+        for (key, value) in [
+            ("tls", "YES"),
+            ("port", " 8080 "),
+            ("tags", ""),
+            ("weight", "0.5"),
+        ] {
+            tree.insert(key, value.to_string(), None);
+        }
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Partial {
+            tls: bool,
+            port: u16,
+            tags: Vec<String>,
+            weight: Option<f64>,
+        }
+        let expected = Partial {
+            tls: true,
+            port: 8080,
+            tags: Vec::new(),
+            weight: Some(0.5),
+        };
+        assert_eq!(Partial::deserialize(tree).unwrap(), expected);
+    }
+
+    #[test]
+    fn must_attach_line_of_invalid_value() {
+        let mut tree = StringTree::default();
+        tree.insert("name", "app".to_string(), Some(1));
+        tree.insert("servers.0.host", "localhost".to_string(), Some(2));
+        tree.insert("servers.0.port", "x".to_string(), Some(3));
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Config {
+            name: String,
+            servers: Vec<Server>,
+        }
+        let error = Config::deserialize(tree).unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert!(error.message.contains("invalid u16"), "{error}");
+    }
+
+    #[test]
+    fn must_reject_top_level_scalar() {
+        assert!(to_string_tree(&1).is_err());
+        assert_eq!(to_string_tree(&BTreeMap::<String, String>::new()).unwrap(), StringTree::default());
+    }
+}
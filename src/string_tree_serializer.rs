@@ -0,0 +1,475 @@
+use crate::errors::string_tree_error::StringTreeError;
+use crate::string_tree::StringTree;
+use serde::ser::{Error, Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
+use serde::{Serialize, Serializer};
+
+/// Converts a struct or a map to a [`StringTree`] (the formats built on it have no top-level scalars or sequences)
+pub fn to_string_tree<T: ?Sized + Serialize>(value: &T) -> Result<StringTree, StringTreeError> {
+    let tree = value.serialize(StringTreeSerializer)?.unwrap_or_default();
+    match tree.value.as_deref() {
+        Some(value) if tree.is_leaf() && !value.is_empty() => Err(StringTreeError::custom(format!("expected a struct or a map at the top level, found the value {value:?}"))),
+        _ => Ok(tree),
+    }
+}
+
+/// Converts a value to a [`StringTree`] (`None` for the values that are omitted, e.g. `None` fields)
+///
+/// The sequences get indexed keys (an empty sequence is an empty value), the enum variants other than the unit ones are written as a single child named after the variant.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct StringTreeSerializer;
+
+impl Serializer for StringTreeSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.collect_seq(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf("")))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(StringTree::leaf(variant)))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        let value = value.serialize(self)?.unwrap_or_default();
+        Ok(Some(wrap_variant(Some(variant), value)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SeqSerializer::new(None, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(SeqSerializer::new(None, Some(len)))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(SeqSerializer::new(None, Some(len)))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SeqSerializer::new(Some(variant), Some(len)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapSerializer::new(None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(MapSerializer::new(None))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(MapSerializer::new(Some(variant)))
+    }
+}
+
+pub struct SeqSerializer {
+    variant: Option<&'static str>,
+    items: Vec<StringTree>,
+}
+
+impl SeqSerializer {
+    fn new(variant: Option<&'static str>, len: Option<usize>) -> Self {
+        Self {
+            variant,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+        }
+    }
+
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), StringTreeError> {
+        // A `None` item keeps its index, so it is written as an empty value (which is read back as `None`)
+        let item = value
+            .serialize(StringTreeSerializer)?
+            .unwrap_or_else(|| StringTree::leaf(""));
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<StringTree>, StringTreeError> {
+        let tree = match self.items.is_empty() {
+            true => StringTree::leaf(""),
+            false => StringTree {
+                value: None,
+                children: self
+                    .items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| (index.to_string(), item))
+                    .collect(),
+                line: None,
+            },
+        };
+        Ok(Some(wrap_variant(self.variant, tree)))
+    }
+}
+
+impl SerializeSeq for SeqSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for SeqSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for SeqSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for SeqSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+pub struct MapSerializer {
+    variant: Option<&'static str>,
+    tree: StringTree,
+    key: Option<String>,
+}
+
+impl MapSerializer {
+    fn new(variant: Option<&'static str>) -> Self {
+        Self {
+            variant,
+            tree: StringTree::default(),
+            key: None,
+        }
+    }
+
+    fn insert<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), StringTreeError> {
+        // The dots are read back as nesting (e.g. `a.b=1` is the key `b` under `a`), so a dotted key wouldn't round-trip
+        if key.contains('.') {
+            return Err(StringTreeError::custom(format!("the key {key:?} contains a dot, which would be read back as a nested key")));
+        }
+        if let Some(value) = value.serialize(StringTreeSerializer)? {
+            self.tree.children.push((key.to_string(), value));
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<StringTree>, StringTreeError> {
+        Ok(Some(wrap_variant(self.variant, self.tree)))
+    }
+}
+
+impl SerializeMap for MapSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| StringTreeError::custom("serialize_value was called before serialize_key"))?;
+        self.insert(&key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for MapSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for MapSerializer {
+    type Ok = Option<StringTree>;
+    type Error = StringTreeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+fn wrap_variant(variant: Option<&'static str>, tree: StringTree) -> StringTree {
+    match variant {
+        Some(variant) => StringTree {
+            value: None,
+            children: vec![(variant.to_string(), tree)],
+            line: None,
+        },
+        None => tree,
+    }
+}
+
+/// Accepts only the scalar keys, because the keys are written as text
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported(kind: &str) -> StringTreeError {
+        StringTreeError::custom(format!("a map key must be a string, a number, a bool or a unit variant, found a {kind}"))
+    }
+}
+
+impl Serializer for KeySerializer {
+    type Ok = String;
+    type Error = StringTreeError;
+    type SerializeSeq = Impossible<String, StringTreeError>;
+    type SerializeTuple = Impossible<String, StringTreeError>;
+    type SerializeTupleStruct = Impossible<String, StringTreeError>;
+    type SerializeTupleVariant = Impossible<String, StringTreeError>;
+    type SerializeMap = Impossible<String, StringTreeError>;
+    type SerializeStruct = Impossible<String, StringTreeError>;
+    type SerializeStructVariant = Impossible<String, StringTreeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(Self::unsupported("byte array"))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::unsupported("None"))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Self::unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(Self::unsupported("unit struct"))
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<Self::Ok, Self::Error> {
+        Err(Self::unsupported("newtype variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Self::unsupported("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Self::unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(Self::unsupported("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(Self::unsupported("tuple variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Self::unsupported("map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(Self::unsupported("struct"))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(Self::unsupported("struct variant"))
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::{self, Write};
+
+/// Writes into a `String`, checking the UTF-8 of each chunk as it arrives (a character split across two writes is kept until it is complete)
+#[derive(Default, Debug)]
+pub struct StringWriter {
+    pub output: String,
+    /// The start of a character that the next write completes
+    pending: Vec<u8>,
+}
+
+impl StringWriter {
+    /// Returns [`io::ErrorKind::InvalidData`] if the output ends in the middle of a character
+    pub fn into_string(self) -> io::Result<String> {
+        match self.pending.is_empty() {
+            true => Ok(self.output),
+            false => Err(io::Error::new(io::ErrorKind::InvalidData, "the output ends with an incomplete UTF-8 character")),
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match std::str::from_utf8(bytes) {
+            Ok(string) => self.output.push_str(string),
+            // An incomplete character at the end may be completed by the next write
+            Err(error) if error.error_len().is_none() => {
+                let (valid, rest) = bytes.split_at(error.valid_up_to());
+                self.output
+                    .push_str(std::str::from_utf8(valid).map_err(invalid_data)?);
+                self.pending.extend_from_slice(rest);
+            }
+            Err(error) => return Err(invalid_data(error)),
+        }
+        Ok(())
+    }
+}
+
+impl Write for StringWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        while !self.pending.is_empty() {
+            let Some((byte, tail)) = rest.split_first() else {
+                return Ok(buf.len());
+            };
+            self.pending.push(*byte);
+            rest = tail;
+            match std::str::from_utf8(&self.pending) {
+                Ok(string) => {
+                    self.output.push_str(string);
+                    self.pending.clear();
+                }
+                Err(error) if error.error_len().is_none() => {}
+                Err(error) => return Err(invalid_data(error)),
+            }
+        }
+        self.push(rest)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn invalid_data(error: std::str::Utf8Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_join_characters_split_across_writes() {
+        let mut writer = StringWriter::default();
+        let bytes = "aé€😀".as_bytes();
+        for chunk in bytes.chunks(1) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.into_string().unwrap(), "aé€😀");
+    }
+
+    #[test]
+    fn must_reject_invalid_utf8() {
+        let mut writer = StringWriter::default();
+        assert_eq!(writer.write_all(b"a\xffb").unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let mut writer = StringWriter::default();
+        writer.write_all(&"é".as_bytes()[..1]).unwrap();
+        assert_eq!(writer.into_string().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, VariantArray};
+
+/// When [`ManyWriter`](crate::many_writer::ManyWriter) fsyncs the file (a flush only hands the items over to the OS, which may lose them on a crash)
+#[derive(Serialize, Deserialize, Display, VariantArray, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SyncPolicy {
+    #[default]
+    Never,
+    OnFinish,
+    /// Fsync after every flush (see [`FlushPolicy`](crate::flush_policy::FlushPolicy))
+    OnFlush,
+}
@@ -0,0 +1,355 @@
+use crate::errors::deserialize_many_error::DeserializeManyError;
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::serialize_one_error::SerializeOneError;
+use crate::errors::transcode_error::TranscodeError;
+use crate::errors::transcode_many_error::TranscodeManyError;
+use crate::format::Format;
+use crate::format_options::FormatOptions;
+use crate::loss_check::{LossCheck, LossCheckContext, LossCheckFailure};
+use crate::many_options::ManyOptions;
+#[cfg(feature = "toml")]
+use crate::transcode_value::TomlDatetimes;
+use crate::transcode_value::TranscodeValue;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::io::{BufRead, Read, Write};
+
+/// Converts between the formats without a Rust type
+///
+/// The values that the output format can't represent as is (e.g. `null` in TOML) are reported as [`LossyValueError`](crate::errors::lossy_value_error::LossyValueError) instead of being dropped or replaced.
+impl Format {
+    pub fn transcode(from: Format, to: Format, reader: impl Read, writer: &mut impl Write) -> Result<(), TranscodeError> {
+        Self::transcode_with(from, to, reader, writer, &FormatOptions::default())
+    }
+
+    /// Converts a single document
+    ///
+    /// JSON, YAML and XML inputs are streamed from the deserializer directly into the serializer (unless the output is TOML or CSV, which are rendered in memory anyway); the other inputs are read into a [`TranscodeValue`] first.
+    /// A CSV input is converted as a sequence of records; a CSV output is written from a sequence of records (or from a single record).
+    /// An XML output has the `<root>` element, unless the input is XML too: then the root element keeps its name, which is read from the input first (so the input is read into memory).
+    /// The TOML datetimes stay datetimes in a TOML output and are written as strings to the other formats.
+    /// If the conversion fails, a streamed output is already partially written.
+    #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
+    pub fn transcode_with(from: Format, to: Format, mut reader: impl Read, writer: &mut impl Write, options: &FormatOptions) -> Result<(), TranscodeError> {
+        let context = LossCheckContext::new(from, to);
+        match (from, to) {
+            #[cfg(feature = "csv")]
+            (_, Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv) => {
+                let values = match read_transcode_value(from, reader, options)? {
+                    TranscodeValue::Seq(items) => items,
+                    value => vec![value],
+                };
+                write_csv_records(values, writer, options.csv.unwrap_or_else(|| to.csv_options()), options, &context).map_err(|error| match error {
+                    CsvRecordsError::Csv(error) => SerializeOneError::Csv(error).into(),
+                    CsvRecordsError::Lossy(error) => error.into(),
+                })
+            }
+            #[cfg(feature = "serde_json")]
+            (Format::Json, _) if is_streamed(to) => {
+                let mut deserializer = serde_json::Deserializer::from_reader(reader);
+                transcode_deserializer(crate::json_numbers::JsonNumbers(&mut deserializer), writer, options, &context)?;
+                deserializer.end().map_err(DeserializeOneError::from)?;
+                Ok(())
+            }
+            #[cfg(feature = "serde_yaml")]
+            (Format::Yaml, _) if is_streamed(to) => transcode_deserializer(serde_yaml::Deserializer::from_reader(reader), writer, options, &context),
+            #[cfg(feature = "quick-xml")]
+            (Format::Xml, Format::Xml) => {
+                let mut bytes = Vec::new();
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(|error| DeserializeOneError::new(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io(error)))?;
+                let root = xml_root_name(&bytes).unwrap_or_else(|| "root".to_string());
+                let mut deserializer = quick_xml::de::Deserializer::from_reader(bytes.as_slice());
+                let transcoder = serde_transcode::Transcoder::new(&mut deserializer);
+                serialize_xml_with_root(&LossCheck::new(&transcoder, &context), &root, writer, options).map_err(|error| classify_one_error(error, &context))
+            }
+            #[cfg(feature = "quick-xml")]
+            (Format::Xml, _) if is_streamed(to) => transcode_deserializer(&mut quick_xml::de::Deserializer::from_reader(std::io::BufReader::new(reader)), writer, options, &context),
+            #[cfg(feature = "toml")]
+            (_, Format::Toml) => {
+                let value = read_transcode_value(from, reader, options)?;
+                serialize_one_transcoded(&LossCheck::new(TomlDatetimes(&value), &context), writer, options, &context)
+            }
+            _ => {
+                let value = read_transcode_value(from, reader, options)?;
+                serialize_one_transcoded(&LossCheck::new(&value, &context), writer, options, &context)
+            }
+        }
+    }
+
+    pub fn transcode_many(from: Format, to: Format, reader: impl BufRead + 'static, writer: &mut impl Write) -> Result<(), TranscodeManyError> {
+        Self::transcode_many_with(from, to, reader, writer, &ManyOptions::default())
+    }
+
+    /// Converts the items one by one (e.g. JSONL records to CSV rows or to YAML documents), keeping only the current item in memory
+    ///
+    /// The options apply to both sides (e.g. `csv` overrides the dialect of both the CSV input and the CSV output).
+    /// If the input fails in the middle, the items before the failure are already written.
+    #[allow(unreachable_patterns, unused_variables, unreachable_code, unused_mut)]
+    pub fn transcode_many_with(from: Format, to: Format, reader: impl BufRead + 'static, writer: &mut impl Write, options: &ManyOptions) -> Result<(), TranscodeManyError> {
+        let items = read_transcode_values(from, reader, options)?;
+        let input_error = RefCell::new(None);
+        let values = items.map_while(|item| {
+            item.map_err(|error| *input_error.borrow_mut() = Some(error))
+                .ok()
+        });
+        let context = LossCheckContext::new(from, to);
+        let result = match to {
+            #[cfg(feature = "csv")]
+            Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+                let csv_options = options.format.csv.unwrap_or_else(|| to.csv_options());
+                write_csv_records(values, writer, csv_options, &options.format, &context).map_err(|error| match error {
+                    CsvRecordsError::Csv(error) => crate::errors::serialize_many_error::SerializeManyError::Csv(error).into(),
+                    CsvRecordsError::Lossy(error) => error.into(),
+                })
+            }
+            #[cfg(feature = "toml")]
+            Format::Toml => {
+                let items = values
+                    .enumerate()
+                    .map(|(index, value)| LossCheck::at_index(TomlDatetimes(value), &context, index));
+                to.serialize_many_items_to_writer_with(items, writer, options)
+                    .map_err(|error| classify_many_error(error, &context))
+            }
+            _ => {
+                let items = values
+                    .enumerate()
+                    .map(|(index, value)| LossCheck::at_index(value, &context, index));
+                to.serialize_many_items_to_writer_with(items, writer, options)
+                    .map_err(|error| classify_many_error(error, &context))
+            }
+        };
+        match input_error.take() {
+            Some(error) => Err(error.into()),
+            None => result,
+        }
+    }
+}
+
+#[cfg_attr(not(any(feature = "serde_json", feature = "serde_yaml", feature = "quick-xml")), allow(dead_code))]
+fn transcode_deserializer<'de>(deserializer: impl serde::Deserializer<'de>, writer: &mut impl Write, options: &FormatOptions, context: &LossCheckContext) -> Result<(), TranscodeError> {
+    let transcoder = serde_transcode::Transcoder::new(deserializer);
+    serialize_one_transcoded(&LossCheck::new(&transcoder, context), writer, options, context)
+}
+
+#[allow(unreachable_patterns)]
+fn serialize_one_transcoded(value: &impl Serialize, writer: &mut impl Write, options: &FormatOptions, context: &LossCheckContext) -> Result<(), TranscodeError> {
+    let result = match context.to {
+        #[cfg(feature = "quick-xml")]
+        Format::Xml => context
+            .to
+            .serialize_one_to_writer_with(&XmlRoot(value), writer, options),
+        to => to.serialize_one_to_writer_with(value, writer, options),
+    };
+    result.map_err(|error| classify_one_error(error, context))
+}
+
+/// quick-xml takes the name of the root element from the name of the top-level struct, but a transcoded value has no name
+#[cfg(feature = "quick-xml")]
+struct XmlRoot<T>(T);
+
+#[cfg(feature = "quick-xml")]
+impl<T: Serialize> Serialize for XmlRoot<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("root", &self.0)
+    }
+}
+
+/// Writes the value as XML with the root element of the input (quick-xml takes the name of the root element from a `&'static str` otherwise, see [`XmlRoot`])
+#[cfg(feature = "quick-xml")]
+fn serialize_xml_with_root(value: &impl Serialize, root: &str, writer: &mut impl Write, options: &FormatOptions) -> Result<(), SerializeOneError> {
+    let mut string = String::new();
+    let mut serializer = quick_xml::se::Serializer::with_root(&mut string, Some(root))?;
+    if options.layout.is_pretty(false) {
+        serializer.indent(options.indent_char, options.indent_width);
+    }
+    if options.canonical {
+        crate::canonical_value::CanonicalValue::from_serialize(value)?.serialize(serializer)?;
+    } else {
+        value.serialize(serializer)?;
+    }
+    if options.trailing_newline && !string.ends_with('\n') {
+        string.push('\n');
+    }
+    writer
+        .write_all(string.as_bytes())
+        .map_err(SerializeOneError::Io)
+}
+
+/// Returns the name of the root element, `None` if the input has no elements (or is invalid before the first one)
+#[cfg(feature = "quick-xml")]
+fn xml_root_name(input: &[u8]) -> Option<String> {
+    use quick_xml::events::Event;
+    let mut reader = quick_xml::Reader::from_reader(input);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(element) | Event::Empty(element) => return String::from_utf8(element.name().as_ref().to_vec()).ok(),
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+/// The transcoder reports the errors of the input through the serializer, so the origin is taken from the context
+fn classify_one_error(error: SerializeOneError, context: &LossCheckContext) -> TranscodeError {
+    match context.take_failure() {
+        Some(LossCheckFailure::Lossy(error)) => error.into(),
+        Some(LossCheckFailure::Input) => input_error(context.from, input_message(error)).into(),
+        _ => error.into(),
+    }
+}
+
+fn classify_many_error(error: crate::errors::serialize_many_error::SerializeManyError, context: &LossCheckContext) -> TranscodeManyError {
+    match context.take_failure() {
+        Some(LossCheckFailure::Lossy(error)) => error.into(),
+        _ => TranscodeManyError::Serialize(error),
+    }
+}
+
+/// Returns the message of the input error without the prefix that the canonicalizing serializer adds to it
+fn input_message(error: SerializeOneError) -> String {
+    match error {
+        SerializeOneError::Canonicalize(error) => error.message,
+        error => error.to_string(),
+    }
+}
+
+/// Recreates the error of the input from its message
+#[allow(unreachable_patterns, unused_variables)]
+fn input_error(format: Format, message: String) -> DeserializeOneError {
+    match format {
+        #[cfg(feature = "serde_json")]
+        Format::Json => <serde_json::Error as serde::de::Error>::custom(message).into(),
+        #[cfg(feature = "serde_yaml")]
+        Format::Yaml => <serde_yaml::Error as serde::de::Error>::custom(message).into(),
+        #[cfg(feature = "quick-xml")]
+        Format::Xml => quick_xml::DeError::Custom(message).into(),
+        _ => DeserializeOneError::new(crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))),
+    }
+}
+
+#[allow(unreachable_patterns, unused_variables, unused_mut)]
+pub(crate) fn read_transcode_value(format: Format, reader: impl Read, options: &FormatOptions) -> Result<TranscodeValue, DeserializeOneError> {
+    match format {
+        #[cfg(feature = "csv")]
+        Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+            let records = options
+                .csv
+                .unwrap_or_else(|| format.csv_options())
+                .reader_builder()
+                .from_reader(reader)
+                .into_deserialize::<CsvRecordValue>()
+                .map(|record| record.map(|record| record.0))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(TranscodeValue::Seq(records))
+        }
+        // Like the streamed conversions, also when serde-xml-rs is the XML backend of the typed API
+        #[cfg(feature = "quick-xml")]
+        Format::Xml => Ok(quick_xml::de::from_reader(std::io::BufReader::new(reader))?),
+        _ => format.deserialize_one_from_reader_with(reader, options),
+    }
+}
+
+#[allow(unreachable_patterns)]
+pub(crate) fn read_transcode_values(format: Format, reader: impl BufRead + 'static, options: &ManyOptions) -> Result<Box<dyn Iterator<Item = Result<TranscodeValue, DeserializeOneError>>>, DeserializeManyError> {
+    match format {
+        #[cfg(feature = "csv")]
+        Format::Csv | Format::Tsv | Format::Psv | Format::SemicolonCsv => {
+            let records = format.deserialize_many_from_reader_with::<CsvRecordValue>(reader, options)?;
+            Ok(Box::new(records.map(|record| record.map(|record| record.0))))
+        }
+        _ => format.deserialize_many_from_reader_with::<TranscodeValue>(reader, options),
+    }
+}
+
+/// TOML is rendered in memory anyway (and its serializer may need to look at a value more than once), so the input is read into a [`TranscodeValue`] first
+#[cfg_attr(not(any(feature = "serde_json", feature = "serde_yaml", feature = "quick-xml")), allow(dead_code))]
+#[allow(unreachable_patterns, unused_variables)]
+fn is_streamed(format: Format) -> bool {
+    match format {
+        #[cfg(feature = "toml")]
+        Format::Toml => false,
+        _ => true,
+    }
+}
+
+/// A CSV record deserialized as a map (or as a sequence if the input has no headers)
+#[cfg(feature = "csv")]
+#[derive(serde::Deserialize)]
+#[serde(transparent)]
+struct CsvRecordValue(#[serde(deserialize_with = "TranscodeValue::deserialize_record")] TranscodeValue);
+
+#[cfg(feature = "csv")]
+enum CsvRecordsError {
+    Csv(csv::Error),
+    Lossy(crate::errors::lossy_value_error::LossyValueError),
+}
+
+/// Writes the maps as records with the header taken from the keys of the first map (the sequences and the scalars are written as records without a header)
+#[cfg(feature = "csv")]
+fn write_csv_records(values: impl IntoIterator<Item = TranscodeValue>, writer: impl Write, mut csv_options: crate::csv_options::CsvOptions, options: &FormatOptions, context: &LossCheckContext) -> Result<(), CsvRecordsError> {
+    use crate::lossy_value_kind::LossyValueKind;
+    if options.canonical {
+        csv_options.terminator = crate::csv_terminator::CsvTerminator::Lf;
+    }
+    let mut writer = csv_options.writer_builder().from_writer(writer);
+    let mut headers: Option<Vec<String>> = None;
+    let lossy = |kind, path: String| {
+        CsvRecordsError::Lossy(crate::errors::lossy_value_error::LossyValueError {
+            kind,
+            path,
+            from: context.from,
+            to: context.to,
+        })
+    };
+    let field = |value: TranscodeValue, path: String| {
+        value
+            .to_scalar_string()
+            .ok_or_else(|| lossy(LossyValueKind::NestedValue, path))
+    };
+    for (index, value) in values.into_iter().enumerate() {
+        let fields = match value {
+            TranscodeValue::Map(entries) => {
+                let mut record = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let key = field(key, format!("[{index}]"))?;
+                    record.push((key, value));
+                }
+                let headers = match &mut headers {
+                    Some(headers) => headers,
+                    None => {
+                        let keys = record
+                            .iter()
+                            .map(|(key, _)| key.clone())
+                            .collect::<Vec<_>>();
+                        if csv_options.has_headers {
+                            writer.write_record(&keys).map_err(CsvRecordsError::Csv)?;
+                        }
+                        headers.insert(keys)
+                    }
+                };
+                let mut fields = vec![String::new(); headers.len()];
+                for (key, value) in record {
+                    let path = format!("[{index}].{key}");
+                    let position = headers.iter().position(|header| *header == key);
+                    match position.and_then(|position| fields.get_mut(position)) {
+                        Some(slot) => *slot = field(value, path)?,
+                        None => return Err(lossy(LossyValueKind::UnknownColumn, path)),
+                    }
+                }
+                fields
+            }
+            TranscodeValue::Seq(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(position, item)| field(item, format!("[{index}][{position}]")))
+                .collect::<Result<Vec<_>, _>>()?,
+            value => vec![field(value, format!("[{index}]"))?],
+        };
+        writer.write_record(&fields).map_err(CsvRecordsError::Csv)?;
+    }
+    writer
+        .flush()
+        .map_err(|error| CsvRecordsError::Csv(error.into()))
+}
@@ -0,0 +1,262 @@
+use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "toml")]
+use std::borrow::Borrow;
+use std::fmt::Formatter;
+
+/// The key of the single-entry map that the TOML deserializer passes a datetime as
+#[cfg(feature = "toml")]
+const TOML_DATETIME_KEY: &str = "$__toml_private_datetime";
+
+/// An in-memory copy of a self-described value (the map entries keep their original order)
+///
+/// Used for transcoding when the value can't be streamed directly from the deserializer into the serializer (e.g. the items of a many-item input).
+#[derive(PartialEq, Clone, Debug)]
+pub enum TranscodeValue {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<TranscodeValue>),
+    Map(Vec<(TranscodeValue, TranscodeValue)>),
+    /// A TOML datetime (written as a string, except to TOML, see [`TomlDatetimes`])
+    #[cfg(feature = "toml")]
+    Datetime(String),
+}
+
+impl TranscodeValue {
+    /// Deserializes a map (or a sequence, if the deserializer decides so), which is required for the deserializers that are not self-describing at the top level (e.g. CSV records)
+    pub fn deserialize_record<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(TranscodeValueVisitor)
+    }
+
+    /// Returns the string representation of a scalar value (`None` for sequences and maps)
+    pub fn to_scalar_string(&self) -> Option<String> {
+        use TranscodeValue::*;
+        match self {
+            Null => Some(String::new()),
+            Bool(value) => Some(value.to_string()),
+            I64(value) => Some(value.to_string()),
+            U64(value) => Some(value.to_string()),
+            I128(value) => Some(value.to_string()),
+            U128(value) => Some(value.to_string()),
+            F64(value) => Some(value.to_string()),
+            Str(value) => Some(value.clone()),
+            #[cfg(feature = "toml")]
+            Datetime(value) => Some(value.clone()),
+            Bytes(_) | Seq(_) | Map(_) => None,
+        }
+    }
+
+    /// Returns `true` if the values hold the same data regardless of the order of the map entries and of the types of the numbers (e.g. `1` and `1.0` are the same, but `9007199254740993` and `9007199254740992.0` are not)
+    pub fn is_same_data(&self, other: &Self) -> bool {
+        use TranscodeValue::*;
+        match (self, other) {
+            (Seq(left), Seq(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right)
+                        .all(|(left, right)| left.is_same_data(right))
+            }
+            (Map(left), Map(right)) => {
+                fn sorted(entries: &[(TranscodeValue, TranscodeValue)]) -> Vec<&(TranscodeValue, TranscodeValue)> {
+                    let mut entries = entries.iter().collect::<Vec<_>>();
+                    entries.sort_by_cached_key(|(key, _)| key.to_scalar_string());
+                    entries
+                }
+                left.len() == right.len()
+                    && sorted(left)
+                        .into_iter()
+                        .zip(sorted(right))
+                        .all(|((left_key, left_value), (right_key, right_value))| left_key.is_same_data(right_key) && left_value.is_same_data(right_value))
+            }
+            #[cfg(feature = "toml")]
+            (Datetime(left), Datetime(right)) => match (left.parse::<toml::value::Datetime>(), right.parse::<toml::value::Datetime>()) {
+                (Ok(left), Ok(right)) => left == right,
+                _ => left == right,
+            },
+            (F64(left), F64(right)) => left == right || (left.is_nan() && right.is_nan()),
+            (I64(_) | U64(_) | I128(_) | U128(_) | F64(_), I64(_) | U64(_) | I128(_) | U128(_) | F64(_)) => match (self.to_i128(), other.to_i128()) {
+                (Some(left), Some(right)) => left == right,
+                _ => matches!((self, other), (U128(left), U128(right)) if left == right),
+            },
+            (left, right) => left == right,
+        }
+    }
+
+    /// Returns the integer value of a number (`None` for the fractional floats and for the integers out of the `i128` range)
+    fn to_i128(&self) -> Option<i128> {
+        use TranscodeValue::*;
+        match self {
+            I64(value) => Some(i128::from(*value)),
+            U64(value) => Some(i128::from(*value)),
+            I128(value) => Some(*value),
+            U128(value) => i128::try_from(*value).ok(),
+            // The floats in this range convert exactly
+            F64(value) if value.fract() == 0.0 && value.abs() < 2f64.powi(127) => Some(*value as i128),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for TranscodeValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use TranscodeValue::*;
+        match self {
+            Null => serializer.serialize_unit(),
+            Bool(value) => serializer.serialize_bool(*value),
+            I64(value) => serializer.serialize_i64(*value),
+            U64(value) => serializer.serialize_u64(*value),
+            I128(value) => serializer.serialize_i128(*value),
+            U128(value) => serializer.serialize_u128(*value),
+            F64(value) => serializer.serialize_f64(*value),
+            Str(value) => serializer.serialize_str(value),
+            Bytes(value) => serializer.serialize_bytes(value),
+            Seq(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            #[cfg(feature = "toml")]
+            Datetime(value) => serializer.serialize_str(value),
+        }
+    }
+}
+
+/// Serializes the value with the datetimes as TOML datetimes (the TOML serializer would write the strings as strings)
+#[cfg(feature = "toml")]
+pub struct TomlDatetimes<T>(pub T);
+
+#[cfg(feature = "toml")]
+impl<T: Borrow<TranscodeValue>> Serialize for TomlDatetimes<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+        match self.0.borrow() {
+            TranscodeValue::Datetime(value) => value
+                .parse::<toml::value::Datetime>()
+                .map_err(S::Error::custom)?
+                .serialize(serializer),
+            TranscodeValue::Seq(items) => serializer.collect_seq(items.iter().map(TomlDatetimes)),
+            TranscodeValue::Map(entries) => serializer.collect_map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (TomlDatetimes(key), TomlDatetimes(value))),
+            ),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TranscodeValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TranscodeValueVisitor)
+    }
+}
+
+struct TranscodeValueVisitor;
+
+impl<'de> Visitor<'de> for TranscodeValueVisitor {
+    type Value = TranscodeValue;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::I64(value))
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::I128(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::U64(value))
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::U128(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::F64(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::Str(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::Str(value))
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        self.visit_byte_buf(value.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        // Some text formats pass strings as bytes (e.g. the CSV headers), so the valid UTF-8 is kept as a string
+        Ok(match String::from_utf8(value) {
+            Ok(string) => TranscodeValue::Str(string),
+            Err(error) => TranscodeValue::Bytes(error.into_bytes()),
+        })
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(TranscodeValue::Null)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(TranscodeValue::Seq(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        match entries.as_slice() {
+            #[cfg(feature = "toml")]
+            [(TranscodeValue::Str(key), TranscodeValue::Str(value))] if key == TOML_DATETIME_KEY => Ok(TranscodeValue::Datetime(value.clone())),
+            #[cfg(feature = "serde_json")]
+            [(TranscodeValue::Str(key), TranscodeValue::Str(number))] if key == crate::json_numbers::JSON_NUMBER_KEY => crate::json_numbers::visit_number(number, TranscodeValueVisitor),
+            _ => Ok(TranscodeValue::Map(entries)),
+        }
+    }
+}
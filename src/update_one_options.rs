@@ -0,0 +1,9 @@
+use crate::lock_options::LockOptions;
+use crate::save_one_options::SaveOneOptions;
+
+/// Options for [`Format::update_one_with`](crate::format::Format::update_one_with)
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug)]
+pub struct UpdateOneOptions {
+    pub lock: LockOptions,
+    pub save: SaveOneOptions,
+}
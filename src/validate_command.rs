@@ -0,0 +1,59 @@
+use crate::cli_io::{input_format, is_many, read_input};
+use crate::errors::cli_error::CliError;
+use crate::errors::invalid_files_error::InvalidFilesError;
+use crate::errors::load_many_error::LoadManyError;
+use crate::errors::load_one_error::LoadOneError;
+use crate::format_options::FormatOptions;
+use crate::many_options::ManyOptions;
+use crate::transcode::{read_transcode_value, read_transcode_values};
+use clap::Args;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Clone, Debug)]
+pub struct ValidateCommand {
+    /// The files to check (`-` for stdin)
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// The format of the files (detected from the extension or from the content by default)
+    #[arg(long)]
+    pub format: Option<crate::format::Format>,
+    /// Check the items one by one (e.g. YAML documents), implied for JSON Lines
+    #[arg(long)]
+    pub many: bool,
+}
+
+impl ValidateCommand {
+    /// Checks all files, printing the error of each invalid file to stderr
+    pub fn run(self) -> Result<(), CliError> {
+        let mut invalid_count = 0usize;
+        for path in &self.paths {
+            if let Err(error) = self.validate_file(path) {
+                eprintln!("{}", error.render());
+                invalid_count = invalid_count.saturating_add(1);
+            }
+        }
+        match invalid_count {
+            0 => Ok(()),
+            invalid_count => Err(InvalidFilesError {
+                invalid_count,
+                total_count: self.paths.len(),
+            }
+            .into()),
+        }
+    }
+
+    /// Reports only the first error of the file
+    fn validate_file(&self, path: &Path) -> Result<(), CliError> {
+        let input = read_input(path)?;
+        let format = input_format(self.format, path, &input)?;
+        let result = if is_many(format, self.many) {
+            read_transcode_values(format, Cursor::new(input.clone()), &ManyOptions::default())
+                .map_err(|error| LoadManyError::new(path, format, error))?
+                .try_for_each(|item| item.map(drop))
+        } else {
+            read_transcode_value(format, input.as_slice(), &FormatOptions::default()).map(drop)
+        };
+        result.map_err(|error| LoadOneError::from_input(path, format, error, &input).into())
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Writes a file via a sibling temp file that is renamed over `path` once `write` succeeds.
+///
+/// The temp file is created in the same directory as `path` (so that the rename doesn't cross filesystems), fsynced before the rename, and the directory is fsynced after the rename (on Unix).
+/// If `write` fails, the temp file is removed and `path` is left untouched.
+/// If `path` already exists, its permissions are copied to the new file.
+pub fn write_atomically<E: From<io::Error>>(path: impl AsRef<Path>, write: impl FnOnce(&mut File) -> Result<(), E>) -> Result<(), E> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut prefix = std::ffi::OsString::from(".");
+    if let Some(file_name) = path.file_name() {
+        prefix.push(file_name);
+    }
+    prefix.push(".");
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        // Match `File::create` (0o666 minus umask) instead of the 0o600 that `tempfile` uses by default
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+    match std::fs::metadata(path) {
+        Ok(metadata) => temp.as_file().set_permissions(metadata.permissions())?,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error.into()),
+    }
+    write(temp.as_file_mut())?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|error| error.error)?;
+    sync_dir(dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+pub fn sync_dir(_dir: &Path) -> io::Result<()> {
+    // Directories can't be opened as files on other platforms; the rename itself is durable enough there
+    Ok(())
+}
+
+/// Async version of [`write_atomically`] that writes the whole `contents`
+///
+/// The blocking [`write_atomically`] runs on the blocking thread pool of tokio.
+#[cfg(feature = "async")]
+pub async fn write_atomically_async(path: impl AsRef<Path>, contents: Vec<u8>) -> io::Result<()> {
+    let path = path.as_ref().to_path_buf();
+    crate::spawn_blocking::spawn_blocking(move || write_atomically(path, |file| io::Write::write_all(file, &contents))).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn entries(dir: &Path) -> Vec<std::ffi::OsString> {
+        let mut entries = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn must_keep_target_when_write_fails_midway() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "old").unwrap();
+        let result = write_atomically(&path, |file| {
+            file.write_all(b"new, but only the first half")?;
+            Err(io::Error::other("serialization failed"))
+        });
+        assert_eq!(result.unwrap_err().to_string(), "serialization failed");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(entries(dir.path()), vec!["config.json"]);
+    }
+
+    #[test]
+    fn must_leave_no_temp_file_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        write_atomically(&path, |file| file.write_all(b"new")).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(entries(dir.path()), vec!["config.json"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn must_preserve_permissions_of_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.json");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        write_atomically(&path, |file| file.write_all(b"new")).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn must_return_not_found_for_missing_parent_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("config.json");
+        let error = write_atomically(&path, |file| file.write_all(b"new")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(!path.exists());
+        assert_eq!(entries(dir.path()), Vec::<std::ffi::OsString>::new());
+    }
+}
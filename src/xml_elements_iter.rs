@@ -0,0 +1,289 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use quick_xml::events::Event;
+use quick_xml::{DeError, Reader, Writer};
+use serde::de::DeserializeOwned;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// Lazily deserializes the child elements of the root element of an XML document
+///
+/// Only the events of the current child element are kept in memory: they are written back into a buffer, which is then passed to [`quick_xml::de::from_reader`].
+/// An invalid child element (or a child element with an unexpected name) yields an error without stopping the iteration; malformed XML (or an unexpected root element) yields an error and stops it.
+pub struct XmlElementsIter<R, T> {
+    reader: Reader<R>,
+    root_tag: String,
+    item_tag: String,
+    state: XmlElementsIterState,
+    buffer: Vec<u8>,
+    phantom: PhantomData<T>,
+}
+
+struct XmlElement {
+    name: Vec<u8>,
+    bytes: Vec<u8>,
+    /// The byte offset of the element in the document
+    offset: usize,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+enum XmlElementsIterState {
+    Start,
+    Elements,
+    Trailing,
+    Finished,
+}
+
+impl<R: BufRead, T: DeserializeOwned> XmlElementsIter<R, T> {
+    pub fn new(reader: R, root_tag: impl Into<String>, item_tag: impl Into<String>) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            root_tag: root_tag.into(),
+            item_tag: item_tag.into(),
+            state: XmlElementsIterState::Start,
+            buffer: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    /// Returns `true` if the root element has child elements
+    fn start(&mut self) -> Result<bool, DeserializeOneError> {
+        loop {
+            self.buffer.clear();
+            match self
+                .reader
+                .read_event_into(&mut self.buffer)
+                .map_err(DeError::from)?
+            {
+                Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_) => {}
+                Event::Text(text) if is_whitespace(&text) => {}
+                Event::Start(start) if start.name().as_ref() == self.root_tag.as_bytes() => return Ok(true),
+                Event::Empty(start) if start.name().as_ref() == self.root_tag.as_bytes() => return Ok(false),
+                Event::Start(start) | Event::Empty(start) => return Err(custom_error(format!("expected the root element <{}>, found <{}>", self.root_tag, String::from_utf8_lossy(start.name().as_ref())))),
+                Event::Eof => return Err(custom_error(format!("expected the root element <{}>, found end of input", self.root_tag))),
+                event => return Err(custom_error(format!("expected the root element <{}>, found {event:?}", self.root_tag))),
+            }
+        }
+    }
+
+    /// Returns `None` if the root element has ended
+    fn read_element(&mut self) -> Result<Option<XmlElement>, DeserializeOneError> {
+        loop {
+            let offset = self.offset();
+            self.buffer.clear();
+            let event = self
+                .reader
+                .read_event_into(&mut self.buffer)
+                .map_err(DeError::from)?;
+            match event {
+                Event::Comment(_) | Event::PI(_) => {}
+                Event::Text(text) if is_whitespace(&text) => {}
+                Event::End(_) => return Ok(None),
+                Event::Empty(start) => {
+                    let name = start.name().as_ref().to_vec();
+                    let mut writer = Writer::new(Vec::new());
+                    writer
+                        .write_event(Event::Empty(start))
+                        .map_err(DeserializeOneErrorKind::Io)?;
+                    return Ok(Some(XmlElement {
+                        name,
+                        bytes: writer.into_inner(),
+                        offset,
+                    }));
+                }
+                Event::Start(start) => {
+                    let name = start.name().as_ref().to_vec();
+                    let mut writer = Writer::new(Vec::new());
+                    writer
+                        .write_event(Event::Start(start))
+                        .map_err(DeserializeOneErrorKind::Io)?;
+                    let mut depth = 1usize;
+                    while depth != 0 {
+                        self.buffer.clear();
+                        let event = self
+                            .reader
+                            .read_event_into(&mut self.buffer)
+                            .map_err(DeError::from)?;
+                        match &event {
+                            Event::Start(_) => depth = depth.saturating_add(1),
+                            Event::End(_) => depth = depth.saturating_sub(1),
+                            Event::Eof => return Err(custom_error(format!("unexpected end of input inside <{}>", String::from_utf8_lossy(&name)))),
+                            _ => {}
+                        }
+                        writer
+                            .write_event(event)
+                            .map_err(DeserializeOneErrorKind::Io)?;
+                    }
+                    return Ok(Some(XmlElement {
+                        name,
+                        bytes: writer.into_inner(),
+                        offset,
+                    }));
+                }
+                Event::Eof => return Err(custom_error(format!("unexpected end of input inside <{}>", self.root_tag))),
+                event => return Err(custom_error(format!("expected a child element of <{}>, found {event:?}", self.root_tag))),
+            }
+        }
+    }
+
+    /// Returns the byte offset of the reader (the errors of the reader and of the structure are located at it, because the elements are read one by one)
+    fn offset(&self) -> usize {
+        usize::try_from(self.reader.buffer_position()).unwrap_or(usize::MAX)
+    }
+
+    fn expect_end_of_input(&mut self) -> Result<(), DeserializeOneError> {
+        loop {
+            self.buffer.clear();
+            match self
+                .reader
+                .read_event_into(&mut self.buffer)
+                .map_err(DeError::from)?
+            {
+                Event::Comment(_) | Event::PI(_) => {}
+                Event::Text(text) if is_whitespace(&text) => {}
+                Event::Eof => return Ok(()),
+                event => return Err(custom_error(format!("expected end of input after the root element, found {event:?}"))),
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for XmlElementsIter<R, T> {
+    type Item = Result<T, DeserializeOneError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == XmlElementsIterState::Start {
+            match self.start() {
+                Ok(true) => self.state = XmlElementsIterState::Elements,
+                Ok(false) => self.state = XmlElementsIterState::Trailing,
+                Err(error) => {
+                    self.state = XmlElementsIterState::Finished;
+                    return Some(Err(error.with_offset(self.offset())));
+                }
+            }
+        }
+        match self.state {
+            XmlElementsIterState::Elements => match self.read_element() {
+                Ok(Some(element)) if element.name == self.item_tag.as_bytes() => Some(serde_path_to_error::deserialize(&mut quick_xml::de::Deserializer::from_reader(element.bytes.as_slice())).map_err(|error| DeserializeOneError::from_path_to_error(error).with_offset(element.offset))),
+                Ok(Some(element)) => Some(Err(custom_error(format!("expected the item element <{}>, found <{}>", self.item_tag, String::from_utf8_lossy(&element.name))).with_offset(element.offset))),
+                Ok(None) => {
+                    self.state = XmlElementsIterState::Finished;
+                    self.expect_end_of_input()
+                        .err()
+                        .map(|error| Err(error.with_offset(self.offset())))
+                }
+                Err(error) => {
+                    self.state = XmlElementsIterState::Finished;
+                    Some(Err(error.with_offset(self.offset())))
+                }
+            },
+            XmlElementsIterState::Trailing => {
+                self.state = XmlElementsIterState::Finished;
+                self.expect_end_of_input()
+                    .err()
+                    .map(|error| Err(error.with_offset(self.offset())))
+            }
+            XmlElementsIterState::Start | XmlElementsIterState::Finished => None,
+        }
+    }
+}
+
+/// The whitespace between the elements is not trimmed by the reader, because the whitespace inside the elements must be preserved
+fn is_whitespace(bytes: &[u8]) -> bool {
+    bytes.iter().all(u8::is_ascii_whitespace)
+}
+
+fn custom_error(message: String) -> DeserializeOneError {
+    DeError::Custom(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Item {
+        name: String,
+        port: u16,
+    }
+
+    fn items(input: &str) -> Vec<Result<Item, DeserializeOneError>> {
+        XmlElementsIter::new(input.as_bytes(), "items", "item").collect()
+    }
+
+    #[test]
+    fn must_read_items_one_by_one() {
+        let input = "<?xml version=\"1.0\"?>\n<!-- header -->\n<items>\n  <item><name> a </name><port>1</port></item>\n  <!-- between -->\n  <item><name>b</name><port>2</port></item>\n</items>\n";
+        let items = items(input)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![" a ", "b"]
+        );
+        assert_eq!(items[1].port, 2);
+    }
+
+    #[test]
+    fn must_read_empty_root() {
+        assert!(items("<items/>").is_empty());
+        assert!(items("<items>\n</items>").is_empty());
+    }
+
+    #[test]
+    fn must_continue_after_invalid_item() {
+        let items = items("<items>\n<item><name>a</name><port>x</port></item>\n<other/>\n<item><name>c</name><port>3</port></item>\n</items>");
+        assert_eq!(items.len(), 3);
+        let error = items[0].as_ref().unwrap_err();
+        assert_eq!(error.location.and_then(|location| location.offset), Some(8));
+        assert!(
+            items[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("found <other>")
+        );
+        assert_eq!(items[2].as_ref().unwrap().port, 3);
+    }
+
+    #[test]
+    fn must_stop_on_malformed_document() {
+        let items = items("<items><item><name>a</name><port>1</port></item><item><name>");
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert!(
+            items[1]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("unexpected end of input")
+        );
+        let items = self::items("<root><item/></root>");
+        assert_eq!(items.len(), 1);
+        assert!(
+            items[0]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("expected the root element <items>, found <root>")
+        );
+        let items = self::items("<items></items><items></items>");
+        assert_eq!(items.len(), 1);
+        assert!(
+            items[0]
+                .as_ref()
+                .unwrap_err()
+                .to_string()
+                .contains("expected end of input")
+        );
+    }
+}
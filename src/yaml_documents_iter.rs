@@ -0,0 +1,285 @@
+use crate::errors::deserialize_one_error::DeserializeOneError;
+use crate::errors::deserialize_one_error_kind::DeserializeOneErrorKind;
+use serde::de::DeserializeOwned;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+/// Lazily deserializes the documents of a multi-document YAML stream
+///
+/// The stream is split into documents at the `---` and `...` markers (YAML forbids them at the start of a line inside the content, so the split needs no parsing), and only the lines of the current document are kept in memory.
+/// Each document is parsed separately, so an invalid document (including a syntax error) yields an error without stopping the iteration; the error locations are translated from the document to the whole stream.
+/// Only the empty document at the end of the stream (e.g. after a trailing `---`, or the whole stream if it has no content) is skipped. The other empty documents and the `null` documents are deserialized as `T` like any other document.
+pub struct YamlDocumentsIter<R, T> {
+    reader: R,
+    /// The marker line that starts the next document (read while looking for the end of the current one)
+    next_marker: Option<Vec<u8>>,
+    /// 0-based line of the next line in the input
+    line: usize,
+    /// Byte offset of the next line in the input
+    offset: usize,
+    finished: bool,
+    phantom: PhantomData<T>,
+}
+
+/// The lines of a document and its position in the input
+#[derive(Default, Debug)]
+struct YamlDocument {
+    bytes: Vec<u8>,
+    /// 0-based line of the first line
+    line: usize,
+    offset: usize,
+    /// Starts with a `---` marker
+    is_explicit: bool,
+    /// Has a line that is not blank, a comment or a directive
+    has_content: bool,
+    has_directives: bool,
+}
+
+impl<R: BufRead, T: DeserializeOwned> YamlDocumentsIter<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            next_marker: None,
+            line: 0,
+            offset: 0,
+            finished: false,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns the next document, `None` if the rest of the stream has no documents
+    fn read_document(&mut self) -> std::io::Result<Option<YamlDocument>> {
+        let mut document = self.empty_document();
+        if let Some(marker) = self.next_marker.take() {
+            self.advance(&marker);
+            document.push_line(&marker);
+        }
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                // The empty explicit document at the end of the stream is skipped
+                let is_document = document.has_content || document.has_directives;
+                return Ok(is_document.then_some(document));
+            }
+            if is_marker(&line, b"---") && (document.has_content || document.is_explicit) {
+                self.next_marker = Some(line);
+                return Ok(Some(document));
+            }
+            // The comments and the directives before the first `---` belong to its document
+            self.advance(&line);
+            document.push_line(&line);
+            if is_marker(&line, b"...") {
+                if document.has_content || document.is_explicit {
+                    return Ok(Some(document));
+                }
+                document = self.empty_document();
+            }
+        }
+    }
+
+    fn empty_document(&self) -> YamlDocument {
+        YamlDocument {
+            line: self.line,
+            offset: self.offset,
+            ..YamlDocument::default()
+        }
+    }
+
+    fn advance(&mut self, line: &[u8]) {
+        self.offset = self.offset.saturating_add(line.len());
+        if line.ends_with(b"\n") {
+            self.line = self.line.saturating_add(1);
+        }
+    }
+}
+
+impl YamlDocument {
+    fn push_line(&mut self, line: &[u8]) {
+        let content = match line.strip_prefix(b"---") {
+            Some(rest) if is_marker(line, b"---") => {
+                self.is_explicit = true;
+                rest
+            }
+            _ => line,
+        };
+        match content.trim_ascii_start().first() {
+            None | Some(b'#') => {}
+            Some(b'%') if !self.is_explicit && !self.has_content => self.has_directives = true,
+            Some(_) => self.has_content = true,
+        }
+        self.bytes.extend_from_slice(line);
+    }
+
+    /// Deserializes the document, translating the error location from the document to the whole stream
+    fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DeserializeOneError> {
+        serde_path_to_error::deserialize(serde_yaml::Deserializer::from_slice(&self.bytes))
+            .map_err(DeserializeOneError::from_path_to_error)
+            .map_err(|mut error| {
+                if let Some(location) = error.location.as_mut() {
+                    location.line = location.line.map(|line| line.saturating_add(self.line));
+                    location.offset = location
+                        .offset
+                        .map(|offset| offset.saturating_add(self.offset));
+                }
+                error
+            })
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for YamlDocumentsIter<R, T> {
+    type Item = Result<T, DeserializeOneError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_document() {
+            Ok(Some(document)) => Some(document.deserialize()),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(DeserializeOneErrorKind::Io(error).into()))
+            }
+        }
+    }
+}
+
+/// Returns `true` if the line is the `marker` followed by a space, a tab or the end of the line
+fn is_marker(line: &[u8], marker: &[u8]) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| matches!(rest.first(), None | Some(b' ' | b'\t' | b'\r' | b'\n')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Item {
+        id: u32,
+    }
+
+    fn collect<T: DeserializeOwned>(input: &str) -> Vec<Result<T, String>> {
+        YamlDocumentsIter::<_, T>::new(std::io::BufReader::with_capacity(1, input.as_bytes()))
+            .map(|result| result.map_err(|error| error.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn must_yield_each_document() {
+        assert_eq!(
+            collect::<Item>("id: 1\n---\nid: 2\n"),
+            vec![
+                Ok(Item {
+                    id: 1
+                }),
+                Ok(Item {
+                    id: 2
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn must_skip_trailing_empty_document() {
+        assert_eq!(
+            collect::<Item>("id: 1\n---\n"),
+            vec![Ok(Item {
+                id: 1
+            })]
+        );
+        assert_eq!(
+            collect::<Item>("id: 1\n--- # end\n# comment\n"),
+            vec![Ok(Item {
+                id: 1
+            })]
+        );
+        assert_eq!(collect::<Item>(""), vec![]);
+        assert_eq!(collect::<Item>("# only a comment\n"), vec![]);
+    }
+
+    #[test]
+    fn must_not_skip_explicit_null_document() {
+        assert_eq!(
+            collect::<Option<Item>>("id: 1\n---\nnull\n"),
+            vec![
+                Ok(Some(Item {
+                    id: 1
+                })),
+                Ok(None)
+            ]
+        );
+    }
+
+    #[test]
+    fn must_report_empty_document_in_the_middle() {
+        let results = collect::<Item>("id: 1\n---\n---\nid: 3\n");
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_err());
+        assert_eq!(
+            results[2],
+            Ok(Item {
+                id: 3
+            })
+        );
+    }
+
+    #[test]
+    fn must_continue_after_invalid_document() {
+        let results = collect::<Item>("id: 1\n---\nid: x\n---\nid: 3\n");
+        assert_eq!(results.len(), 3);
+        assert!(results[1].as_ref().unwrap_err().contains("id"));
+        assert_eq!(
+            results[2],
+            Ok(Item {
+                id: 3
+            })
+        );
+    }
+
+    #[test]
+    fn must_continue_after_syntax_error() {
+        let results = collect::<Item>("id: 1\n---\na: b: c\n---\nid: 3\n");
+        assert_eq!(
+            results,
+            vec![
+                Ok(Item {
+                    id: 1
+                }),
+                Err("3:5: mapping values are not allowed in this context".to_string()),
+                Ok(Item {
+                    id: 3
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn must_locate_errors_in_the_whole_stream() {
+        let results = collect::<Item>("# items\nid: 1\n...\n---\n\nid: x\n");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1], Err("6:5: id: invalid type: string \"x\", expected u32".to_string()));
+    }
+
+    #[test]
+    fn must_read_markers_with_content_and_directives() {
+        assert_eq!(
+            collect::<String>("%YAML 1.2\n# first\n--- a\n--- |\n  b\n...\n# between\n---\nc\n"),
+            vec![
+                Ok("a".to_string()),
+                Ok("b\n".to_string()),
+                Ok("c".to_string())
+            ]
+        );
+        assert_eq!(collect::<String>("---\n----\n--- \"a\"\n"), vec![Ok("----".to_string()), Ok("a".to_string())]);
+    }
+}